@@ -0,0 +1,15 @@
+//! This module contains the import/export routines from/to standard circuit formats
+//!
+//! * [qasm] - OpenQASM 2.0 and 3.0
+//! * [stim] - Stim circuits, Pauli strings and tableaux
+pub mod qasm;
+pub mod stim;
+
+pub use qasm::{
+    circuit_from_qasm, circuit_to_qasm, clifford_circuit_from_qasm, clifford_circuit_to_qasm,
+    QasmError, QasmVersion,
+};
+pub use stim::{
+    clifford_circuit_from_stim, clifford_circuit_to_stim, pauli_set_from_stim, pauli_set_to_stim,
+    tableau_from_stim, tableau_to_stim, StimError,
+};
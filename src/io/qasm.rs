@@ -0,0 +1,820 @@
+//! OpenQASM 2.0 and 3.0 import/export.
+//!
+//! Only the unitary part of the languages is supported: qubit registers, standard gates and
+//! barriers (which are ignored). Classical registers are ignored, while measurements, resets,
+//! classical control and custom gate definitions are rejected.
+//!
+//! ```
+//! use rustiq_core::io::{circuit_from_qasm, circuit_to_qasm, QasmVersion};
+//! let source = "OPENQASM 2.0;
+//! include \"qelib1.inc\";
+//! qreg a[1];
+//! qreg b[2];
+//! h a[0];
+//! cx a[0], b[1];
+//! rz(pi/4) b[1];
+//! ";
+//! let circuit = circuit_from_qasm(source).unwrap();
+//! assert_eq!(circuit.nqbits, 3);
+//! let output = circuit_to_qasm(&circuit, QasmVersion::V3).unwrap();
+//! assert_eq!(circuit_from_qasm(&output).unwrap(), circuit);
+//! ```
+use crate::structures::{Circuit, CliffordCircuit, CliffordGate, Gate, Parameter};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// Version of the OpenQASM language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QasmVersion {
+    V2,
+    V3,
+}
+
+/// Error raised while reading or writing OpenQASM code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QasmError {
+    /// Line of the source where the error occurred (starting at 1), 0 if the error is not
+    /// related to a particular line
+    pub line: usize,
+    pub message: String,
+}
+
+impl QasmError {
+    fn new(line: usize, message: String) -> Self {
+        Self { line, message }
+    }
+}
+
+impl std::fmt::Display for QasmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for QasmError {}
+
+/// Standard gates understood by the parser, together with their number of parameters and qubits
+const SUPPORTED_GATES: [(&str, usize, usize); 21] = [
+    ("id", 0, 1),
+    ("x", 0, 1),
+    ("y", 0, 1),
+    ("z", 0, 1),
+    ("h", 0, 1),
+    ("s", 0, 1),
+    ("sdg", 0, 1),
+    ("t", 0, 1),
+    ("tdg", 0, 1),
+    ("sx", 0, 1),
+    ("sxdg", 0, 1),
+    ("cx", 0, 2),
+    ("CX", 0, 2),
+    ("cz", 0, 2),
+    ("swap", 0, 2),
+    ("rx", 1, 1),
+    ("ry", 1, 1),
+    ("rz", 1, 1),
+    ("p", 1, 1),
+    ("u1", 1, 1),
+    ("phase", 1, 1),
+];
+
+/* ----------------------------------- Emission ----------------------------------- */
+
+fn header(version: QasmVersion, nqbits: usize) -> String {
+    match version {
+        QasmVersion::V2 => format!(
+            "OPENQASM 2.0;\ninclude \"qelib1.inc\";\nqreg q[{}];\n",
+            nqbits
+        ),
+        QasmVersion::V3 => format!(
+            "OPENQASM 3.0;\ninclude \"stdgates.inc\";\nqubit[{}] q;\n",
+            nqbits
+        ),
+    }
+}
+
+fn clifford_gate_to_qasm(gate: &CliffordGate) -> String {
+    match gate {
+        CliffordGate::CNOT(i, j) => format!("cx q[{}], q[{}];\n", i, j),
+        CliffordGate::CZ(i, j) => format!("cz q[{}], q[{}];\n", i, j),
+        CliffordGate::H(i) => format!("h q[{}];\n", i),
+        CliffordGate::S(i) => format!("s q[{}];\n", i),
+        CliffordGate::Sd(i) => format!("sdg q[{}];\n", i),
+        CliffordGate::SqrtX(i) => format!("sx q[{}];\n", i),
+        CliffordGate::SqrtXd(i) => format!("sxdg q[{}];\n", i),
+    }
+}
+
+/// `stdgates.inc` does not define the sxdg gate
+const SXDG_DEFINITION: &str = "gate sxdg a { inv @ sx a; }";
+
+fn sxdg_definition(version: QasmVersion, gates: &[CliffordGate]) -> String {
+    let uses_sxdg = gates.iter().any(|g| matches!(g, CliffordGate::SqrtXd(_)));
+    if version == QasmVersion::V3 && uses_sxdg {
+        format!("{}\n", SXDG_DEFINITION)
+    } else {
+        String::new()
+    }
+}
+
+/// Converts a Clifford circuit into OpenQASM code, using a single register `q`
+pub fn clifford_circuit_to_qasm(circuit: &CliffordCircuit, version: QasmVersion) -> String {
+    let mut output = header(version, circuit.nqbits);
+    output.push_str(&sxdg_definition(version, &circuit.gates));
+    for gate in circuit.gates.iter() {
+        output.push_str(&clifford_gate_to_qasm(gate));
+    }
+    output
+}
+
+/// Converts a circuit into OpenQASM code, using a single register `q`.
+///
+/// Pauli rotations acting on several qubits are decomposed into Clifford gates and RZ gates.
+/// Abstract parameters are emitted verbatim and, in OpenQASM 3.0, declared as `input` angles.
+/// Since OpenQASM 2.0 does not support free parameters, abstract parameters are rejected in
+/// this version.
+pub fn circuit_to_qasm(circuit: &Circuit, version: QasmVersion) -> Result<String, QasmError> {
+    let mut circuit = circuit.clone();
+    if circuit
+        .gates
+        .iter()
+        .any(|gate| matches!(gate, Gate::PauliRotation(_, _)))
+    {
+        circuit = circuit.decompose_rotations();
+    }
+    let mut inputs: Vec<String> = Vec::new();
+    let mut body = String::new();
+    for gate in circuit.gates.iter() {
+        let (name, qbit, angle) = match gate {
+            Gate::Clifford(gate) => {
+                body.push_str(&clifford_gate_to_qasm(gate));
+                continue;
+            }
+            Gate::Rz(qbit, angle) => ("rz", qbit, angle),
+            Gate::Rx(qbit, angle) => ("rx", qbit, angle),
+            Gate::Ry(qbit, angle) => ("ry", qbit, angle),
+            Gate::PauliRotation(_, _) => unreachable!(),
+        };
+        if let Parameter::Abstract(expr) = angle {
+            if version == QasmVersion::V2 {
+                return Err(QasmError::new(
+                    0,
+                    format!("OpenQASM 2.0 does not support free parameters ({})", expr),
+                ));
+            }
+            for token in tokenize(expr).map_err(|message| QasmError::new(0, message))? {
+                if let Token::Identifier(name) = token {
+                    if name != "pi" && name != "π" && !inputs.contains(&name) {
+                        inputs.push(name);
+                    }
+                }
+            }
+        }
+        body.push_str(&format!("{}({}) q[{}];\n", name, angle, qbit));
+    }
+    let mut output = header(version, circuit.nqbits);
+    for input in inputs.iter() {
+        output.push_str(&format!("input angle {};\n", input));
+    }
+    output.push_str(&sxdg_definition(
+        version,
+        &circuit.clifford_skeleton().gates,
+    ));
+    output.push_str(&body);
+    Ok(output)
+}
+
+/* ------------------------------------ Parsing ------------------------------------ */
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Symbol(char),
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit()
+                    || chars[i] == '.'
+                    || ((chars[i] == 'e' || chars[i] == 'E') && i + 1 < chars.len())
+                    || ((chars[i] == '+' || chars[i] == '-')
+                        && (chars[i - 1] == 'e' || chars[i - 1] == 'E')))
+            {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let value = literal
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid number `{}`", literal))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Identifier(chars[start..i].iter().collect()));
+        } else if "+-*/()".contains(c) {
+            tokens.push(Token::Symbol(c));
+            i += 1;
+        } else {
+            return Err(format!(
+                "Unexpected character `{}` in expression `{}`",
+                c, expr
+            ));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent evaluation of constant expressions. Returns None if the expression
+/// contains free parameters.
+struct Evaluator {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Evaluator {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+    fn expression(&mut self) -> Result<Option<f64>, String> {
+        let mut value = self.term()?;
+        while let Some(Token::Symbol(op)) = self.peek().cloned() {
+            if op != '+' && op != '-' {
+                break;
+            }
+            self.position += 1;
+            let rhs = self.term()?;
+            value = match (value, rhs) {
+                (Some(a), Some(b)) => Some(if op == '+' { a + b } else { a - b }),
+                _ => None,
+            };
+        }
+        Ok(value)
+    }
+    fn term(&mut self) -> Result<Option<f64>, String> {
+        let mut value = self.factor()?;
+        while let Some(Token::Symbol(op)) = self.peek().cloned() {
+            if op != '*' && op != '/' {
+                break;
+            }
+            self.position += 1;
+            let rhs = self.factor()?;
+            value = match (value, rhs) {
+                (Some(a), Some(b)) => Some(if op == '*' { a * b } else { a / b }),
+                _ => None,
+            };
+        }
+        Ok(value)
+    }
+    fn factor(&mut self) -> Result<Option<f64>, String> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or("Unexpected end of expression".to_owned())?;
+        self.position += 1;
+        match token {
+            Token::Number(value) => Ok(Some(value)),
+            Token::Identifier(name) => match name.as_str() {
+                "pi" | "π" => Ok(Some(PI)),
+                _ => Ok(None),
+            },
+            Token::Symbol('-') => Ok(self.factor()?.map(|value| -value)),
+            Token::Symbol('+') => self.factor(),
+            Token::Symbol('(') => {
+                let value = self.expression()?;
+                if self.peek() != Some(&Token::Symbol(')')) {
+                    return Err("Unbalanced parentheses".to_owned());
+                }
+                self.position += 1;
+                Ok(value)
+            }
+            Token::Symbol(c) => Err(format!("Unexpected symbol `{}`", c)),
+        }
+    }
+}
+
+/// Parses a gate parameter: constant expressions are evaluated, other expressions are kept as
+/// abstract parameters
+fn parse_parameter(expr: &str) -> Result<Parameter, String> {
+    let mut evaluator = Evaluator {
+        tokens: tokenize(expr)?,
+        position: 0,
+    };
+    let value = evaluator.expression()?;
+    if evaluator.position != evaluator.tokens.len() {
+        return Err(format!("Invalid expression `{}`", expr));
+    }
+    Ok(match value {
+        Some(value) => Parameter::Concrete(value),
+        None => Parameter::Abstract(expr.trim().to_owned()),
+    })
+}
+
+/// Splits the source into `(line, statement)` pairs, dropping comments. Gate definitions are
+/// kept as a single statement.
+fn split_statements(source: &str) -> Vec<(usize, String)> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut start_line = 1;
+    let mut line = 1;
+    let mut depth = 0;
+    let chars: Vec<char> = source.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+        if c == '\n' {
+            line += 1;
+        }
+        if current.trim().is_empty() && !c.is_whitespace() {
+            start_line = line;
+        }
+        match c {
+            '{' => {
+                depth += 1;
+                current.push(c);
+            }
+            '}' => {
+                depth -= 1;
+                current.push(c);
+                if depth == 0 {
+                    statements.push((start_line, current.trim().to_owned()));
+                    current.clear();
+                }
+            }
+            ';' if depth == 0 => {
+                statements.push((start_line, current.trim().to_owned()));
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+        i += 1;
+    }
+    if !current.trim().is_empty() {
+        statements.push((start_line, current.trim().to_owned()));
+    }
+    statements
+}
+
+/// Index of the parenthesis closing the one opening `text`
+fn matching_parenthesis(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses `name[index]` or `name`
+fn parse_indexed(text: &str) -> Result<(String, Option<usize>), String> {
+    let text = text.trim();
+    match text.find('[') {
+        None => Ok((text.to_owned(), None)),
+        Some(open) => {
+            if !text.ends_with(']') {
+                return Err(format!("Invalid operand `{}`", text));
+            }
+            let index = text[open + 1..text.len() - 1]
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid index in `{}`", text))?;
+            Ok((text[..open].trim().to_owned(), Some(index)))
+        }
+    }
+}
+
+struct Parser {
+    registers: HashMap<String, (usize, usize)>,
+    nqbits: usize,
+    gates: Vec<(usize, Gate)>,
+}
+
+impl Parser {
+    fn add_register(&mut self, name: &str, size: usize) -> Result<(), String> {
+        if self.registers.contains_key(name) {
+            return Err(format!("Register `{}` is declared twice", name));
+        }
+        self.registers.insert(name.to_owned(), (self.nqbits, size));
+        self.nqbits += size;
+        Ok(())
+    }
+
+    /// Resolves an operand into a list of qubits
+    fn resolve(&self, operand: &str) -> Result<Vec<usize>, String> {
+        let (name, index) = parse_indexed(operand)?;
+        let (offset, size) = self
+            .registers
+            .get(&name)
+            .ok_or(format!("Unknown register `{}`", name))?;
+        match index {
+            None => Ok((*offset..offset + size).collect()),
+            Some(index) if index < *size => Ok(vec![offset + index]),
+            Some(index) => Err(format!(
+                "Index {} is out of range for register `{}` of size {}",
+                index, name, size
+            )),
+        }
+    }
+
+    fn push(&mut self, line: usize, gate: Gate) {
+        self.gates.push((line, gate));
+    }
+
+    fn apply(
+        &mut self,
+        line: usize,
+        name: &str,
+        params: &[Parameter],
+        qbits: &[usize],
+    ) -> Result<(), String> {
+        let q = qbits[0];
+        let clifford = |gate: CliffordGate| Gate::Clifford(gate);
+        match name {
+            "id" => {}
+            "x" => {
+                self.push(line, clifford(CliffordGate::SqrtX(q)));
+                self.push(line, clifford(CliffordGate::SqrtX(q)));
+            }
+            "z" => {
+                self.push(line, clifford(CliffordGate::S(q)));
+                self.push(line, clifford(CliffordGate::S(q)));
+            }
+            "y" => {
+                self.push(line, clifford(CliffordGate::SqrtX(q)));
+                self.push(line, clifford(CliffordGate::S(q)));
+                self.push(line, clifford(CliffordGate::S(q)));
+                self.push(line, clifford(CliffordGate::SqrtXd(q)));
+            }
+            "h" => self.push(line, clifford(CliffordGate::H(q))),
+            "s" => self.push(line, clifford(CliffordGate::S(q))),
+            "sdg" => self.push(line, clifford(CliffordGate::Sd(q))),
+            "sx" => self.push(line, clifford(CliffordGate::SqrtX(q))),
+            "sxdg" => self.push(line, clifford(CliffordGate::SqrtXd(q))),
+            "t" => self.push(line, Gate::Rz(q, Parameter::Concrete(PI / 4.))),
+            "tdg" => self.push(line, Gate::Rz(q, Parameter::Concrete(-PI / 4.))),
+            "rx" => self.push(line, Gate::Rx(q, params[0].clone())),
+            "ry" => self.push(line, Gate::Ry(q, params[0].clone())),
+            "rz" | "p" | "u1" | "phase" => self.push(line, Gate::Rz(q, params[0].clone())),
+            "cx" | "CX" | "cz" | "swap" => {
+                let t = qbits[1];
+                if q == t {
+                    return Err(format!("Gate {} is applied twice on qubit {}", name, q));
+                }
+                match name {
+                    "cz" => self.push(line, clifford(CliffordGate::CZ(q, t))),
+                    "swap" => {
+                        self.push(line, clifford(CliffordGate::CNOT(q, t)));
+                        self.push(line, clifford(CliffordGate::CNOT(t, q)));
+                        self.push(line, clifford(CliffordGate::CNOT(q, t)));
+                    }
+                    _ => self.push(line, clifford(CliffordGate::CNOT(q, t))),
+                }
+            }
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+
+    fn gate_statement(&mut self, line: usize, statement: &str) -> Result<(), String> {
+        let name_end = statement
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(statement.len());
+        let name = &statement[..name_end];
+        let (_, nparams, nqbits) = SUPPORTED_GATES
+            .iter()
+            .find(|(gate, _, _)| *gate == name)
+            .ok_or(format!("Unsupported gate or statement `{}`", name))?;
+        let mut rest = statement[name_end..].trim();
+        let mut params = Vec::new();
+        if rest.starts_with('(') {
+            let close = matching_parenthesis(rest).ok_or("Unbalanced parentheses".to_owned())?;
+            for expr in rest[1..close].split(',') {
+                params.push(parse_parameter(expr)?);
+            }
+            rest = rest[close + 1..].trim();
+        }
+        if params.len() != *nparams {
+            return Err(format!(
+                "Gate {} expects {} parameters, got {}",
+                name,
+                nparams,
+                params.len()
+            ));
+        }
+        let operands: Vec<Vec<usize>> = rest
+            .split(',')
+            .map(|operand| self.resolve(operand))
+            .collect::<Result<_, _>>()?;
+        if operands.len() != *nqbits {
+            return Err(format!(
+                "Gate {} expects {} qubits, got {}",
+                name,
+                nqbits,
+                operands.len()
+            ));
+        }
+        // Register operands are broadcast
+        let width = operands.iter().map(|qbits| qbits.len()).max().unwrap();
+        if operands
+            .iter()
+            .any(|qbits| qbits.len() != 1 && qbits.len() != width)
+        {
+            return Err("Registers of different sizes cannot be broadcast together".to_owned());
+        }
+        for k in 0..width {
+            let qbits: Vec<usize> = operands
+                .iter()
+                .map(|qbits| if qbits.len() == 1 { qbits[0] } else { qbits[k] })
+                .collect();
+            self.apply(line, name, &params, &qbits)?;
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, line: usize, statement: &str) -> Result<(), String> {
+        let keyword = statement.split_whitespace().next().unwrap_or("");
+        let keyword = keyword.split('[').next().unwrap();
+        match keyword {
+            "" | "include" | "barrier" | "input" | "creg" | "bit" => Ok(()),
+            "OPENQASM" => {
+                let version = statement["OPENQASM".len()..].trim();
+                if version.starts_with('2') || version.starts_with('3') {
+                    Ok(())
+                } else {
+                    Err(format!("Unsupported OpenQASM version {}", version))
+                }
+            }
+            "qreg" => {
+                let (name, size) = parse_indexed(&statement["qreg".len()..])?;
+                let size = size.ok_or(format!("Missing size for register `{}`", name))?;
+                self.add_register(&name, size)
+            }
+            "qubit" => {
+                let (_, size) = parse_indexed(statement.split_whitespace().next().unwrap())?;
+                let name = statement[statement.find(char::is_whitespace).unwrap_or(0)..].trim();
+                if name.is_empty() {
+                    return Err("Missing register name".to_owned());
+                }
+                self.add_register(name, size.unwrap_or(1))
+            }
+            "gate" => {
+                let name = statement["gate".len()..]
+                    .split(|c: char| c.is_whitespace() || c == '(' || c == '{')
+                    .find(|s| !s.is_empty())
+                    .unwrap_or("");
+                let without_spaces = |s: &str| s.split_whitespace().collect::<String>();
+                if without_spaces(statement) == without_spaces(SXDG_DEFINITION) {
+                    // The definition written by our own exporter
+                    Ok(())
+                } else if SUPPORTED_GATES.iter().any(|(gate, _, _)| *gate == name) {
+                    Err(format!(
+                        "Redefinition of the standard gate `{}` is not supported",
+                        name
+                    ))
+                } else {
+                    Err(format!(
+                        "Custom gate definitions are not supported (`{}`)",
+                        name
+                    ))
+                }
+            }
+            "measure" | "reset" | "if" | "opaque" | "while" | "for" => {
+                Err(format!("Unsupported statement `{}`", keyword))
+            }
+            _ if is_measure_assignment(statement) => {
+                Err("Unsupported statement `measure`".to_owned())
+            }
+            _ => self.gate_statement(line, statement),
+        }
+    }
+}
+
+/// Detects OpenQASM 3 measurements of the form `c = measure q;` or `c[0] = measure q[0];`
+fn is_measure_assignment(statement: &str) -> bool {
+    let Some((target, value)) = statement.split_once('=') else {
+        return false;
+    };
+    let is_identifier = match parse_indexed(target) {
+        Ok((name, _)) => !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_'),
+        Err(_) => false,
+    };
+    is_identifier && value.split_whitespace().next() == Some("measure")
+}
+
+fn parse(source: &str) -> Result<(usize, Vec<(usize, Gate)>), QasmError> {
+    let mut parser = Parser {
+        registers: HashMap::new(),
+        nqbits: 0,
+        gates: Vec::new(),
+    };
+    for (line, statement) in split_statements(source) {
+        parser
+            .statement(line, &statement)
+            .map_err(|message| QasmError::new(line, message))?;
+    }
+    Ok((parser.nqbits, parser.gates))
+}
+
+/// Parses OpenQASM 2.0 or 3.0 code. Qubit registers are concatenated in order of declaration.
+pub fn circuit_from_qasm(source: &str) -> Result<Circuit, QasmError> {
+    let (nqbits, gates) = parse(source)?;
+    Ok(Circuit {
+        nqbits,
+        gates: gates.into_iter().map(|(_, gate)| gate).collect(),
+    })
+}
+
+/// Parses OpenQASM 2.0 or 3.0 code containing only Clifford gates. Qubit registers are
+/// concatenated in order of declaration.
+pub fn clifford_circuit_from_qasm(source: &str) -> Result<CliffordCircuit, QasmError> {
+    let (nqbits, gates) = parse(source)?;
+    let mut circuit = CliffordCircuit::new(nqbits);
+    for (line, gate) in gates {
+        match gate {
+            Gate::Clifford(gate) => circuit.gates.push(gate),
+            _ => {
+                return Err(QasmError::new(
+                    line,
+                    "Rotation gates are not supported in Clifford circuits".to_owned(),
+                ))
+            }
+        }
+    }
+    Ok(circuit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn random_circuit(n: usize, ngates: usize) -> Circuit {
+        let mut rng = rand::thread_rng();
+        let mut circuit = Circuit::new(n);
+        for _ in 0..ngates {
+            let q = rng.gen_range(0..n);
+            let t = (q + rng.gen_range(1..n)) % n;
+            let angle = Parameter::Concrete(rng.gen_range(-4.0..4.0));
+            circuit.gates.push(match rng.gen_range(0..10) {
+                0 => Gate::Clifford(CliffordGate::CNOT(q, t)),
+                1 => Gate::Clifford(CliffordGate::CZ(q, t)),
+                2 => Gate::Clifford(CliffordGate::H(q)),
+                3 => Gate::Clifford(CliffordGate::S(q)),
+                4 => Gate::Clifford(CliffordGate::Sd(q)),
+                5 => Gate::Clifford(CliffordGate::SqrtX(q)),
+                6 => Gate::Clifford(CliffordGate::SqrtXd(q)),
+                7 => Gate::Rz(q, angle),
+                8 => Gate::Rx(q, angle),
+                _ => Gate::Ry(q, angle),
+            });
+        }
+        circuit
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for _ in 0..10 {
+            let circuit = random_circuit(5, 100);
+            for version in [QasmVersion::V2, QasmVersion::V3] {
+                let source = circuit_to_qasm(&circuit, version).unwrap();
+                assert_eq!(circuit_from_qasm(&source).unwrap(), circuit);
+            }
+            let clifford = circuit.clifford_skeleton();
+            for version in [QasmVersion::V2, QasmVersion::V3] {
+                let source = clifford_circuit_to_qasm(&clifford, version);
+                let parsed = clifford_circuit_from_qasm(&source).unwrap();
+                assert_eq!(parsed.nqbits, clifford.nqbits);
+                assert_eq!(parsed.gates, clifford.gates);
+            }
+        }
+    }
+
+    #[test]
+    fn test_abstract_parameters() {
+        let mut circuit = Circuit::new(2);
+        circuit
+            .gates
+            .push(Gate::Rz(0, Parameter::Abstract("theta".to_owned())));
+        circuit
+            .gates
+            .push(Gate::Rx(1, Parameter::Abstract("2*phi+pi/2".to_owned())));
+        assert!(circuit_to_qasm(&circuit, QasmVersion::V2).is_err());
+        let source = circuit_to_qasm(&circuit, QasmVersion::V3).unwrap();
+        assert!(source.contains("input angle theta;\ninput angle phi;\n"));
+        assert_eq!(circuit_from_qasm(&source).unwrap(), circuit);
+    }
+
+    #[test]
+    fn test_registers_and_expressions() {
+        let source = "OPENQASM 3.0;
+include \"stdgates.inc\";
+qubit[2] a; // first register
+qubit b;
+/* a multi-line
+   comment */
+bit[3] c;
+cx a, b;
+barrier a, b;
+rz(-(pi/2 + 1e-1) * 2) a[1];
+";
+        let circuit = circuit_from_qasm(source).unwrap();
+        assert_eq!(circuit.nqbits, 3);
+        assert_eq!(
+            &circuit.gates[..2],
+            &[
+                Gate::Clifford(CliffordGate::CNOT(0, 2)),
+                Gate::Clifford(CliffordGate::CNOT(1, 2)),
+            ]
+        );
+        match &circuit.gates[2] {
+            Gate::Rz(1, Parameter::Concrete(x)) => assert!((x + PI + 0.2).abs() < 1e-12),
+            gate => panic!("Unexpected gate {:?}", gate),
+        }
+    }
+
+    #[test]
+    fn test_names_containing_keywords() {
+        let source =
+            "OPENQASM 2.0;\nqreg measured[2];\nh measured[0];\ncx measured[0], measured[1];";
+        let circuit = clifford_circuit_from_qasm(source).unwrap();
+        assert_eq!(circuit.gates.len(), 2);
+        let source = "OPENQASM 3.0;\nqubit[1] q;\ngate  sxdg a {\n  inv @ sx a;\n}\nsxdg q[0];";
+        let circuit = clifford_circuit_from_qasm(source).unwrap();
+        assert_eq!(circuit.gates, vec![CliffordGate::SqrtXd(0)]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let check = |source: &str, line: usize| {
+            let error = circuit_from_qasm(source).unwrap_err();
+            assert_eq!(error.line, line, "{}", error);
+        };
+        check("OPENQASM 2.0;\nqreg q[2];\n\nccx q[0], q[1], q[1];", 4);
+        check("OPENQASM 2.0;\nqreg q[2];\nmeasure q[0] -> c[0];", 3);
+        check("OPENQASM 2.0;\nqreg q[2];\nh q[2];", 3);
+        check("OPENQASM 2.0;\nqreg q[2];\n/* \n */ h r[0];", 4);
+        check("OPENQASM 2.0;\nqreg q[2];\ncx q[0];", 3);
+        check(
+            "OPENQASM 2.0;\nqreg q[2];\ngate foo a { h a; }\nfoo q[0];",
+            3,
+        );
+        check("OPENQASM 4.0;", 1);
+        check("OPENQASM 3.0;\nqubit[2] q;\nbit[2] c;\nc = measure q;", 4);
+        check(
+            "OPENQASM 3.0;\nqubit[2] q;\nbit[2] c;\nc[1] = measure q[0];",
+            4,
+        );
+        check("OPENQASM 3.0;\nqubit[2] q;\ngate h a { s a; }\nh q[0];", 3);
+        check(
+            "OPENQASM 3.0;\nqubit[2] q;\ngate sxdg a { sx a; }\nsxdg q[0];",
+            3,
+        );
+        let error =
+            clifford_circuit_from_qasm("OPENQASM 2.0;\nqreg q[1];\nh q[0];\nt q[0];").unwrap_err();
+        assert_eq!(error.line, 4);
+        assert_eq!(
+            error.to_string(),
+            "line 4: Rotation gates are not supported in Clifford circuits"
+        );
+    }
+}
@@ -0,0 +1,543 @@
+//! Stim text formats import/export.
+//!
+//! Three formats are supported:
+//! * circuits (`.stim` files) restricted to unitary Clifford gates. Stim annotations (`TICK`,
+//!   `QUBIT_COORDS`, `SHIFT_COORDS`) are ignored and `REPEAT` blocks are unrolled, while
+//!   measurements, resets, noise channels and detectors are rejected.
+//! * dense Pauli strings (e.g. `-X_YZ`), one per line, for [PauliSet]
+//! * the tableau layout printed by `str(stim.Tableau)` for [Tableau]
+//!
+//! ```
+//! use rustiq_core::io::{clifford_circuit_from_stim, tableau_to_stim};
+//! use rustiq_core::structures::Tableau;
+//! let circuit = clifford_circuit_from_stim("CX 0 1").unwrap();
+//! let tableau = Tableau::from_circuit(&circuit);
+//! assert_eq!(tableau_to_stim(&tableau), "+-xz-xz-\n| ++ ++\n| XZ _Z\n| X_ XZ\n");
+//! ```
+use crate::structures::{CliffordCircuit, CliffordGate, PauliSet, Tableau};
+
+/// Error raised while reading Stim text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StimError {
+    /// Line of the source where the error occurred (starting at 1), 0 if the error is not
+    /// related to a particular line
+    pub line: usize,
+    pub message: String,
+}
+
+impl StimError {
+    fn new(line: usize, message: String) -> Self {
+        Self { line, message }
+    }
+}
+
+impl std::fmt::Display for StimError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for StimError {}
+
+/* ------------------------------------ Circuits ------------------------------------ */
+
+/// Converts a Clifford circuit into a Stim circuit, one instruction per gate
+pub fn clifford_circuit_to_stim(circuit: &CliffordCircuit) -> String {
+    let mut output = String::new();
+    for gate in circuit.gates.iter() {
+        output.push_str(&match gate {
+            CliffordGate::CNOT(i, j) => format!("CX {} {}\n", i, j),
+            CliffordGate::CZ(i, j) => format!("CZ {} {}\n", i, j),
+            CliffordGate::H(i) => format!("H {}\n", i),
+            CliffordGate::S(i) => format!("S {}\n", i),
+            CliffordGate::Sd(i) => format!("S_DAG {}\n", i),
+            CliffordGate::SqrtX(i) => format!("SQRT_X {}\n", i),
+            CliffordGate::SqrtXd(i) => format!("SQRT_X_DAG {}\n", i),
+        });
+    }
+    output
+}
+
+/// Returns the gates implementing a single qubit Stim gate
+fn single_qubit_gate(name: &str, q: usize) -> Option<Vec<CliffordGate>> {
+    Some(match name {
+        "I" => vec![],
+        "X" => vec![CliffordGate::SqrtX(q), CliffordGate::SqrtX(q)],
+        "Y" => vec![
+            CliffordGate::SqrtX(q),
+            CliffordGate::S(q),
+            CliffordGate::S(q),
+            CliffordGate::SqrtXd(q),
+        ],
+        "Z" => vec![CliffordGate::S(q), CliffordGate::S(q)],
+        "H" | "H_XZ" => vec![CliffordGate::H(q)],
+        "S" | "SQRT_Z" => vec![CliffordGate::S(q)],
+        "S_DAG" | "SQRT_Z_DAG" => vec![CliffordGate::Sd(q)],
+        "SQRT_X" => vec![CliffordGate::SqrtX(q)],
+        "SQRT_X_DAG" => vec![CliffordGate::SqrtXd(q)],
+        _ => return None,
+    })
+}
+
+/// Returns the gates implementing a two-qubit Stim gate
+fn two_qubit_gate(name: &str, a: usize, b: usize) -> Option<Vec<CliffordGate>> {
+    Some(match name {
+        "CX" | "CNOT" | "ZCX" => vec![CliffordGate::CNOT(a, b)],
+        "CZ" | "ZCZ" => vec![CliffordGate::CZ(a, b)],
+        "SWAP" => vec![
+            CliffordGate::CNOT(a, b),
+            CliffordGate::CNOT(b, a),
+            CliffordGate::CNOT(a, b),
+        ],
+        _ => return None,
+    })
+}
+
+/// Maximal number of gates of a parsed circuit, once its REPEAT blocks are unrolled
+const MAX_UNROLLED_GATES: usize = 1 << 24;
+
+/// Parses the instructions of `lines` starting at `start` until the end of the input or, inside
+/// the REPEAT block opened at line `repeat_line`, its closing brace. Returns the gates and the
+/// index of the first line that was not consumed.
+fn parse_block(
+    lines: &[&str],
+    start: usize,
+    repeat_line: Option<usize>,
+) -> Result<(Vec<CliffordGate>, usize), StimError> {
+    let mut gates = Vec::new();
+    let mut index = start;
+    while index < lines.len() {
+        let line = index + 1;
+        let text = lines[index].split('#').next().unwrap().trim();
+        index += 1;
+        if text.is_empty() {
+            continue;
+        }
+        if text == "}" {
+            if repeat_line.is_some() {
+                return Ok((gates, index));
+            }
+            return Err(StimError::new(line, "Unexpected `}`".to_owned()));
+        }
+        let mut words = text.split_whitespace();
+        let instruction = words.next().unwrap();
+        let name = instruction.split('(').next().unwrap().to_uppercase();
+        if name == "REPEAT" {
+            let count = words
+                .next()
+                .and_then(|count| count.parse::<usize>().ok())
+                .ok_or(StimError::new(line, "Invalid REPEAT count".to_owned()))?;
+            if words.next() != Some("{") {
+                return Err(StimError::new(line, "Expected `{` after REPEAT".to_owned()));
+            }
+            let (body, next) = parse_block(lines, index, Some(line))?;
+            let total = body
+                .len()
+                .checked_mul(count)
+                .and_then(|size| size.checked_add(gates.len()))
+                .filter(|total| *total <= MAX_UNROLLED_GATES)
+                .ok_or(StimError::new(
+                    line,
+                    format!(
+                        "REPEAT block unrolls to more than {} gates",
+                        MAX_UNROLLED_GATES
+                    ),
+                ))?;
+            gates.reserve(total - gates.len());
+            while gates.len() < total {
+                gates.extend_from_slice(&body);
+            }
+            index = next;
+            continue;
+        }
+        if ["TICK", "QUBIT_COORDS", "SHIFT_COORDS"].contains(&name.as_str()) {
+            continue;
+        }
+        let is_single = single_qubit_gate(&name, 0).is_some();
+        if !is_single && two_qubit_gate(&name, 0, 1).is_none() {
+            return Err(StimError::new(
+                line,
+                format!("Unsupported instruction `{}`", name),
+            ));
+        }
+        if instruction.contains('(') {
+            return Err(StimError::new(
+                line,
+                format!("Gate {} does not take arguments", name),
+            ));
+        }
+        let targets: Vec<usize> = words
+            .map(|target| {
+                target
+                    .parse::<usize>()
+                    .map_err(|_| StimError::new(line, format!("Unsupported target `{}`", target)))
+            })
+            .collect::<Result<_, _>>()?;
+        if is_single {
+            for q in targets {
+                gates.extend(single_qubit_gate(&name, q).unwrap());
+            }
+        } else {
+            let pairs = targets.chunks_exact(2);
+            if !pairs.remainder().is_empty() {
+                return Err(StimError::new(
+                    line,
+                    format!("Gate {} expects an even number of targets", name),
+                ));
+            }
+            for pair in pairs {
+                if pair[0] == pair[1] {
+                    return Err(StimError::new(
+                        line,
+                        format!("Gate {} is applied twice on qubit {}", name, pair[0]),
+                    ));
+                }
+                gates.extend(two_qubit_gate(&name, pair[0], pair[1]).unwrap());
+            }
+        }
+    }
+    if let Some(line) = repeat_line {
+        return Err(StimError::new(line, "Unterminated REPEAT block".to_owned()));
+    }
+    Ok((gates, index))
+}
+
+/// Parses a Stim circuit made of unitary Clifford gates. The number of qubits is the largest
+/// target plus one.
+pub fn clifford_circuit_from_stim(source: &str) -> Result<CliffordCircuit, StimError> {
+    let lines: Vec<&str> = source.lines().collect();
+    let (gates, _) = parse_block(&lines, 0, None)?;
+    let nqbits = gates
+        .iter()
+        .map(|gate| match gate {
+            CliffordGate::CNOT(i, j) | CliffordGate::CZ(i, j) => i.max(j) + 1,
+            CliffordGate::H(i)
+            | CliffordGate::S(i)
+            | CliffordGate::Sd(i)
+            | CliffordGate::SqrtX(i)
+            | CliffordGate::SqrtXd(i) => i + 1,
+        })
+        .max()
+        .unwrap_or(0);
+    let mut circuit = CliffordCircuit::new(nqbits);
+    circuit.gates = gates;
+    Ok(circuit)
+}
+
+/* --------------------------------- Pauli strings --------------------------------- */
+
+fn pauli_string_to_stim(phase: bool, string: &str) -> String {
+    let sign = if phase { '-' } else { '+' };
+    std::iter::once(sign)
+        .chain(string.chars().map(|c| if c == 'I' { '_' } else { c }))
+        .collect()
+}
+
+/// Parses a dense Stim Pauli string (e.g. `-X_YZ`) into a pair (phase, string)
+fn pauli_string_from_stim(text: &str, line: usize) -> Result<(bool, String), StimError> {
+    let text = text.trim();
+    let (phase, body) = match text.chars().next() {
+        Some('+') => (false, &text[1..]),
+        Some('-') => (true, &text[1..]),
+        _ => (false, text),
+    };
+    if body.starts_with('i') {
+        return Err(StimError::new(
+            line,
+            format!("Imaginary Pauli strings are not supported (`{}`)", text),
+        ));
+    }
+    let string = body
+        .chars()
+        .map(|c| match c {
+            '_' | 'I' => Ok('I'),
+            'X' | 'Y' | 'Z' => Ok(c),
+            _ => Err(StimError::new(
+                line,
+                format!("Invalid character `{}` in Pauli string `{}`", c, text),
+            )),
+        })
+        .collect::<Result<String, _>>()?;
+    Ok((phase, string))
+}
+
+/// Converts a PauliSet into Stim Pauli strings, one per line
+pub fn pauli_set_to_stim(pauli_set: &PauliSet) -> String {
+    (0..pauli_set.len())
+        .map(|i| {
+            let (phase, string) = pauli_set.get(i);
+            pauli_string_to_stim(phase, &string) + "\n"
+        })
+        .collect()
+}
+
+/// Parses Stim Pauli strings, one per line. Empty lines are ignored.
+pub fn pauli_set_from_stim(source: &str) -> Result<PauliSet, StimError> {
+    let mut operators: Vec<(bool, String)> = Vec::new();
+    for (index, text) in source.lines().enumerate() {
+        if text.trim().is_empty() {
+            continue;
+        }
+        let operator = pauli_string_from_stim(text, index + 1)?;
+        if let Some((_, first)) = operators.first() {
+            if first.len() != operator.1.len() {
+                return Err(StimError::new(
+                    index + 1,
+                    format!(
+                        "Expected a Pauli string over {} qubits, got {}",
+                        first.len(),
+                        operator.1.len()
+                    ),
+                ));
+            }
+        }
+        operators.push(operator);
+    }
+    let mut pauli_set = PauliSet::new(operators.first().map_or(0, |(_, s)| s.len()));
+    for (phase, string) in operators {
+        pauli_set.insert(&string, phase);
+    }
+    Ok(pauli_set)
+}
+
+/* ------------------------------------ Tableaux ------------------------------------ */
+
+/// Converts a Tableau into the layout printed by `str(stim.Tableau)`: column `xz` of qubit `q`
+/// lists the images of X_q and Z_q, the first row holding their signs.
+pub fn tableau_to_stim(tableau: &Tableau) -> String {
+    let n = tableau.logicals.n;
+    let images: Vec<(bool, String)> = (0..2 * n).map(|i| tableau.logicals.get(i)).collect();
+    let entry = |i: usize, k: usize| match images[i].1.as_bytes()[k] {
+        b'I' => '_',
+        c => c as char,
+    };
+    let mut output = "+-".to_owned() + &"xz-".repeat(n) + "\n|";
+    for q in 0..n {
+        output.push(' ');
+        for i in [q, q + n] {
+            output.push(if images[i].0 { '-' } else { '+' });
+        }
+    }
+    output.push('\n');
+    for k in 0..n {
+        output.push('|');
+        for q in 0..n {
+            output.push(' ');
+            output.push(entry(q, k));
+            output.push(entry(q + n, k));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Parses the layout printed by `str(stim.Tableau)` (see [tableau_to_stim])
+pub fn tableau_from_stim(source: &str) -> Result<Tableau, StimError> {
+    let rows: Vec<(usize, String)> = source
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(index, text)| {
+            (
+                index + 1,
+                text.chars()
+                    .filter(|c| !c.is_whitespace() && *c != '|')
+                    .collect(),
+            )
+        })
+        .collect();
+    let (header_line, header) = rows
+        .first()
+        .ok_or(StimError::new(0, "Empty tableau".to_owned()))?;
+    let n = header.matches("xz").count();
+    if *header != "+-".to_owned() + &"xz-".repeat(n) {
+        return Err(StimError::new(
+            *header_line,
+            "Invalid tableau header".to_owned(),
+        ));
+    }
+    if rows.len() != n + 2 {
+        return Err(StimError::new(
+            0,
+            format!("Expected {} rows for a tableau over {} qubits", n + 2, n),
+        ));
+    }
+    let (signs_line, signs) = &rows[1];
+    if signs.len() != 2 * n || signs.chars().any(|c| c != '+' && c != '-') {
+        return Err(StimError::new(*signs_line, "Invalid sign row".to_owned()));
+    }
+    let signs: Vec<bool> = signs.chars().map(|c| c == '-').collect();
+    let mut images = vec![String::new(); 2 * n];
+    for (line, row) in rows[2..].iter() {
+        let entries: Vec<char> = row.chars().collect();
+        if entries.len() != 2 * n {
+            return Err(StimError::new(*line, "Invalid tableau row".to_owned()));
+        }
+        for q in 0..n {
+            for (image, c) in [(q, entries[2 * q]), (q + n, entries[2 * q + 1])] {
+                images[image].push(match c {
+                    '_' | 'I' => 'I',
+                    'X' | 'Y' | 'Z' => c,
+                    _ => {
+                        return Err(StimError::new(
+                            *line,
+                            format!("Invalid character `{}` in tableau", c),
+                        ))
+                    }
+                });
+            }
+        }
+    }
+    let mut logicals = PauliSet::new(n);
+    for q in 0..n {
+        logicals.insert(&images[q], signs[2 * q]);
+    }
+    for q in 0..n {
+        logicals.insert(&images[q + n], signs[2 * q + 1]);
+    }
+    for i in 0..2 * n {
+        for j in 0..i {
+            // Only the images of X_q and Z_q anticommute
+            if logicals.commute(i, j) == (i == j + n) {
+                return Err(StimError::new(
+                    0,
+                    "The tableau does not describe a Clifford operator".to_owned(),
+                ));
+            }
+        }
+    }
+    Ok(Tableau { logicals })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::PauliLike;
+    use rand::Rng;
+
+    #[test]
+    fn test_circuit_round_trip() {
+        let mut rng = rand::thread_rng();
+        let n = 6;
+        for _ in 0..10 {
+            let mut circuit = CliffordCircuit::new(n);
+            for _ in 0..100 {
+                let q = rng.gen_range(0..n);
+                let t = (q + rng.gen_range(1..n)) % n;
+                circuit.gates.push(match rng.gen_range(0..7) {
+                    0 => CliffordGate::CNOT(q, t),
+                    1 => CliffordGate::CZ(q, t),
+                    2 => CliffordGate::H(q),
+                    3 => CliffordGate::S(q),
+                    4 => CliffordGate::Sd(q),
+                    5 => CliffordGate::SqrtX(q),
+                    _ => CliffordGate::SqrtXd(q),
+                });
+            }
+            let parsed = clifford_circuit_from_stim(&clifford_circuit_to_stim(&circuit)).unwrap();
+            assert_eq!(parsed.gates, circuit.gates);
+            let tableau = Tableau::from_circuit(&circuit);
+            assert_eq!(
+                tableau_from_stim(&tableau_to_stim(&tableau)).unwrap(),
+                tableau
+            );
+        }
+    }
+
+    #[test]
+    fn test_circuit_parsing() {
+        let source = "# Bell pair
+QUBIT_COORDS(0, 0) 0
+H 0
+TICK
+CNOT 0 1 2 3  # two pairs
+REPEAT 2 {
+    S_DAG 1
+}
+SWAP 0 3
+";
+        let circuit = clifford_circuit_from_stim(source).unwrap();
+        assert_eq!(circuit.nqbits, 4);
+        assert_eq!(
+            circuit.gates,
+            vec![
+                CliffordGate::H(0),
+                CliffordGate::CNOT(0, 1),
+                CliffordGate::CNOT(2, 3),
+                CliffordGate::Sd(1),
+                CliffordGate::Sd(1),
+                CliffordGate::CNOT(0, 3),
+                CliffordGate::CNOT(3, 0),
+                CliffordGate::CNOT(0, 3),
+            ]
+        );
+        let check = |source: &str, line: usize| {
+            let error = clifford_circuit_from_stim(source).unwrap_err();
+            assert_eq!(error.line, line, "{}", error);
+        };
+        check("H 0\nM 0", 2);
+        check("H 0\nCX 0 1 2", 2);
+        check("H 0\n\nCX 0 rec[-1]", 3);
+        check("X_ERROR(0.1) 0", 1);
+        check("H 0\nREPEAT 2 {\nH 0\n", 2);
+        check("H 0\n}", 2);
+    }
+
+    #[test]
+    fn test_repeat_limit() {
+        let check = |source: &str, line: usize| {
+            let error = clifford_circuit_from_stim(source).unwrap_err();
+            assert_eq!(error.line, line, "{}", error);
+            assert!(error.message.contains("unrolls"), "{}", error);
+        };
+        check("REPEAT 100000000000 {\nH 0\n}", 1);
+        check("REPEAT 18446744073709551615 {\nH 0\nS 0\n}", 1);
+        // Nested blocks multiply
+        check("H 0\nREPEAT 100000 {\nREPEAT 100000 {\nH 0\n}\n}", 2);
+        // Gates of the enclosing block count too
+        check(
+            "REPEAT 4096 {\nH 0\n}\nREPEAT 4096 {\nREPEAT 4096 {\nH 0\n}\n}",
+            4,
+        );
+        let source = format!("REPEAT {} {{\nH 0\n}}", MAX_UNROLLED_GATES);
+        assert_eq!(
+            clifford_circuit_from_stim(&source).unwrap().gates.len(),
+            MAX_UNROLLED_GATES
+        );
+        let circuit = clifford_circuit_from_stim("REPEAT 100000000000 {\n}").unwrap();
+        assert!(circuit.gates.is_empty());
+    }
+
+    #[test]
+    fn test_stim_conventions() {
+        // Outputs of str(stim.Tableau.from_named_gate(...))
+        let mut circuit = CliffordCircuit::new(1);
+        circuit.gates.push(CliffordGate::SqrtX(0));
+        assert_eq!(
+            tableau_to_stim(&Tableau::from_circuit(&circuit)),
+            "+-xz-\n| +-\n| XY\n"
+        );
+        let mut tableau = Tableau::new(2);
+        tableau.cz(0, 1);
+        assert_eq!(
+            tableau_from_stim("+-xz-xz-\n| ++ ++\n| XZ Z_\n| Z_ XZ\n").unwrap(),
+            tableau
+        );
+        assert!(tableau_from_stim("+-xz-\n| ++\n| XX\n").is_err());
+    }
+
+    #[test]
+    fn test_pauli_set() {
+        let pauli_set = pauli_set_from_stim("+X_YZ\n-ZZII\n\nIIIX\n").unwrap();
+        assert_eq!(pauli_set.len(), 3);
+        assert_eq!(pauli_set.get(0), (false, "XIYZ".to_owned()));
+        assert_eq!(pauli_set.get(1), (true, "ZZII".to_owned()));
+        assert_eq!(pauli_set_to_stim(&pauli_set), "+X_YZ\n-ZZ__\n+___X\n");
+        assert_eq!(pauli_set_from_stim("+XX\n-X").unwrap_err().line, 2);
+        assert_eq!(pauli_set_from_stim("iXX").unwrap_err().line, 1);
+    }
+}
@@ -6,24 +6,78 @@
 //! # Data structures
 //!
 //! * [CliffordCircuit](crate::structures::CliffordCircuit) and [CliffordGate](crate::structures::CliffordGate) - Data structures used to represent Clifford gates and circuits.
+//! * [Circuit](crate::structures::Circuit) and [Gate](crate::structures::Gate) - Circuits interleaving Clifford gates and (parametrized) Pauli rotations.
 //! * [PauliSet](crate::structures::PauliSet) - A data structure used to store a list of Pauli operators. The list can be efficiently conjugated by `CliffordGate` or `CliffordCircuit` objects.
 //! * [Tableau](crate::structures::Tableau) - A Clifford Tableau implementation built on top of `PauliSet`.
 //! * [IsometryTableau](crate::structures::IsometryTableau) - An extension of the Tableau data structure that describes a Clifford operator applied to a partially stabilized input.
 //! * [GraphState](crate::structures::GraphState) - A data structure representing a graph state
 //!
+//! Uniformly random Clifford operators can be sampled from a seed with `Tableau::random_uniform`, `IsometryTableau::random_uniform`
+//! and `CliffordCircuit::random_uniform`, or from any random generator with [sample_clifford](crate::structures::sample_clifford).
+//! Tableaux of up to 7 qubits can also be enumerated using `Tableau::index` and `Tableau::from_index`.
+//!
 //! # Synthesis algorithms
 //!
 //! All synthesis algorithms are located in the [synthesis] submodule.
 //!
 //! * Synthesis of Clifford operators are handled by the [isometry_synthesis](crate::synthesis::clifford::isometry::isometry_synthesis) method.
 //!   This method can handle synthesis of `IsometryTableau` objects into `CliffordCircuit`.
+//!   Results can be memoized in a [SynthesisCache](crate::synthesis::clifford::isometry::SynthesisCache) using
+//!   [isometry_synthesis_cached](crate::synthesis::clifford::isometry::isometry_synthesis_cached).
 //! * Graph states and stabilizer states can be synthesized using [synthesize_graph_state](crate::synthesis::clifford::graph_state::synthesize_graph_state)
 //!   and [synthesize_stabilizer_state](crate::synthesis::clifford::graph_state::synthesize_stabilizer_state).
 //! * Method [codiagonalize](crate::synthesis::clifford::codiagonalization::codiagonalize) can be used to produce a
 //!   `CliffordCircuit` that codiagonalizes a given set of pairwise commuting Pauli operators.
+//! * Method [linear_synthesis](crate::synthesis::linear::linear_synthesis) can be used to synthesize CNOT circuits implementing
+//!   invertible matrices over F2.
+//! * Method [phase_polynomial_synthesis](crate::synthesis::phase_polynomial::phase_polynomial_synthesis) can be used to
+//!   implement a product of Z-rotations with CNOT and RZ gates.
 //! * Method [greedy_pauli_network](crate::synthesis::pauli_network::greedy_pauli_network()) can be used to implement a sequence of Pauli rotations.
+//! * Method [pauli_network_on_hardware](crate::synthesis::pauli_network::pauli_network_on_hardware()) does the same while only using CNOT gates
+//!   allowed by a [HardwareGraph](crate::structures::HardwareGraph).
+//! * Method [optimize_circuit](crate::synthesis::circuit_optimization::optimize_circuit) can be used to optimize a circuit
+//!   made of Clifford gates and Pauli rotations end-to-end.
+//!
+//! # Import/export
+//!
+//! The [io] submodule converts circuits from/to standard formats:
+//!
+//! * OpenQASM 2.0 and 3.0 via [circuit_from_qasm](crate::io::circuit_from_qasm) and [circuit_to_qasm](crate::io::circuit_to_qasm).
+//! * Stim circuits, Pauli strings and tableaux via [clifford_circuit_from_stim](crate::io::clifford_circuit_from_stim),
+//!   [tableau_from_stim](crate::io::tableau_from_stim) and their `*_to_stim` counterparts.
+//!
+//! # Simulation
+//!
+//! The [simulation] submodule contains a dense [StateVector](crate::simulation::StateVector) simulator and
+//! [circuits_equivalent](crate::simulation::circuits_equivalent), which checks that two small circuits implement the same
+//! unitary operator up to a global phase.
+//!
+//! # Verification
+//!
+//! The [verification] submodule checks that Clifford circuits implement a given `Tableau` or `IsometryTableau`, either
+//! exactly or up to Pauli corrections, output qubit permutations or stabilizers, and reports the first differing operator.
+//!
+//! # Serialization
+//!
+//! The optional `serde` feature implements `Serialize` and `Deserialize` for the core data structures.
+//! The encodings are stable across versions:
+//!
+//! * `CliffordGate`, `CliffordCircuit`, `Gate` and `Circuit` use serde's default layout, e.g.
+//!   `{"nqbits": 2, "gates": [{"H": 0}, {"CNOT": [0, 1]}]}`.
+//! * `Parameter` is a number for concrete angles and a string for abstract ones.
+//! * `Metric` is either `"count"` or `"depth"`.
+//! * `PauliSet` is `{"n": 3, "operators": ["+XIZ", "-YYI"]}`, each operator being a sign followed by a Pauli string.
+//! * `Tableau` is `{"n": n, "logicals": [...]}` where the images of X_0, ..., X_{n-1}, Z_0, ..., Z_{n-1} are bit-packed:
+//!   each image is stored as its n X bits, n Z bits and sign bit, little endian, padded to a whole number of
+//!   32-bit words.
+//! * `IsometryTableau` is `{"n": n, "k": k, "logicals": [...], "stabilizers": [...]}` using the same bit-packing
+//!   over n + k qubits.
+//! * `GraphState` is `{"n": 2, "adj": ["01", "11"]}`, listing the rows of the adjacency matrix.
 //!
 
+pub mod io;
 pub mod routines;
+pub mod simulation;
 pub mod structures;
 pub mod synthesis;
+pub mod verification;
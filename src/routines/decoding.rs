@@ -64,14 +64,7 @@ fn shuffle_parities(
     if row_ech {
         let mut rank = 0;
         for i in 0..parities.len() {
-            let mut pivot = None;
-            for j in rank..n {
-                if new_parities[i][j] {
-                    pivot = Some(j);
-                    break;
-                }
-            }
-            if let Some(pivot) = pivot {
+            if let Some(pivot) = (rank..n).find(|j| new_parities[i][*j]) {
                 if pivot != rank {
                     colop(&mut new_parities, pivot, rank);
                     target[rank] ^= target[pivot];
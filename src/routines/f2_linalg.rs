@@ -48,14 +48,7 @@ pub fn row_echelon(table: &mut Matrix, k: usize) {
 pub fn diagonalize(table: &mut Matrix, friend: &mut Matrix, rank: usize) {
     let n = table.first().unwrap().len();
     for i in 0..rank {
-        let mut pivot = None;
-        for j in i..n {
-            if table[i][j] {
-                pivot = Some(j);
-                break;
-            }
-        }
-        if let Some(pivot) = pivot {
+        if let Some(pivot) = (i..n).find(|j| table[i][*j]) {
             if pivot != i {
                 colop(table, pivot, i);
                 colop(friend, pivot, i);
@@ -101,6 +94,65 @@ pub fn f2_rank(table: &Matrix) -> usize {
     rank
 }
 
+/// Reduced row echelon form of a matrix, only the first `ncols` columns being used as pivots.
+/// Returns the reduced matrix and the pivot column of each non-zero row.
+fn reduced_row_echelon(table: &Matrix, ncols: usize) -> (Matrix, Vec<usize>) {
+    let mut table = table.clone();
+    let mut pivots = Vec::new();
+    for col in 0..ncols {
+        let rank = pivots.len();
+        if let Some(pivot) = (rank..table.len()).find(|j| table[*j][col]) {
+            table.swap(pivot, rank);
+            for j in 0..table.len() {
+                if j != rank && table[j][col] {
+                    rowop(&mut table, rank, j);
+                }
+            }
+            pivots.push(col);
+        }
+    }
+    (table, pivots)
+}
+
+/// Solves `table * x = target` over F2, `table` having `ncols` columns. Returns None if the
+/// system has no solution.
+pub fn solve_f2(table: &Matrix, target: &[bool], ncols: usize) -> Option<Vec<bool>> {
+    let augmented: Matrix = table
+        .iter()
+        .zip(target.iter())
+        .map(|(row, b)| {
+            let mut row = row.clone();
+            row.push(*b);
+            row
+        })
+        .collect();
+    let (reduced, pivots) = reduced_row_echelon(&augmented, ncols);
+    if reduced[pivots.len()..].iter().any(|row| row[ncols]) {
+        return None;
+    }
+    let mut solution = vec![false; ncols];
+    for (row, pivot) in reduced.iter().zip(pivots.iter()) {
+        solution[*pivot] = row[ncols];
+    }
+    Some(solution)
+}
+
+/// Returns a basis of the kernel of `table` over F2, `table` having `ncols` columns
+pub fn kernel_f2(table: &Matrix, ncols: usize) -> Matrix {
+    let (reduced, pivots) = reduced_row_echelon(table, ncols);
+    (0..ncols)
+        .filter(|col| !pivots.contains(col))
+        .map(|free| {
+            let mut vector = vec![false; ncols];
+            vector[free] = true;
+            for (row, pivot) in reduced.iter().zip(pivots.iter()) {
+                vector[*pivot] = row[free];
+            }
+            vector
+        })
+        .collect()
+}
+
 pub fn inverse_f2(table: &Matrix) -> Matrix {
     let n = table.len();
     let mut table = table.clone();
@@ -406,4 +458,33 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_solve_and_kernel() {
+        for _ in 0..10 {
+            let matrix = random_skinny(30, 20);
+            let table: Matrix = (0..20)
+                .map(|j| matrix.iter().map(|row| row[j]).collect())
+                .collect();
+            let mut rng = rand::thread_rng();
+            let x: Vec<bool> = (0..30).map(|_| rng.gen_bool(0.5)).collect();
+            let target: Vec<bool> = mult_f2(&table, &x.iter().map(|b| vec![*b]).collect())
+                .into_iter()
+                .map(|row| row[0])
+                .collect();
+            let solution = solve_f2(&table, &target, 30).unwrap();
+            let image = mult_f2(&table, &solution.iter().map(|b| vec![*b]).collect());
+            assert_eq!(image.iter().map(|row| row[0]).collect::<Vec<_>>(), target);
+
+            let kernel = kernel_f2(&table, 30);
+            assert_eq!(kernel.len(), 10);
+            assert_eq!(f2_rank(&kernel), 10);
+            for vector in kernel.iter() {
+                let image = mult_f2(&table, &vector.iter().map(|b| vec![*b]).collect());
+                assert!(image.iter().all(|row| !row[0]));
+            }
+        }
+        let table = vec![vec![true, true], vec![true, true]];
+        assert_eq!(solve_f2(&table, &[true, false], 2), None);
+    }
 }
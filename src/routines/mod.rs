@@ -2,5 +2,9 @@
 
 pub mod decoding;
 pub mod f2_linalg;
+pub mod orientation;
+pub mod placement;
 pub mod rotation_extraction;
 pub mod rotation_optimization;
+pub mod routing;
+pub mod tree_folding;
@@ -0,0 +1,53 @@
+//! Fixing the orientation of CNOT gates on hardware with directed couplings.
+use crate::structures::{CliffordCircuit, CliffordGate, HardwareGraph};
+
+/// Reorients the CNOT gates of a circuit according to the native orientations of `hardware`.
+///
+/// A gate `CNOT(i, j)` is replaced by `H(i) H(j) CNOT(j, i) H(i) H(j)` when `CNOT(j, i)` is
+/// native but `CNOT(i, j)` is not. All other gates are left untouched: in particular, this pass
+/// does not route gates acting on uncoupled qubits.
+pub fn fix_cnot_orientation(
+    circuit: &CliffordCircuit,
+    hardware: &HardwareGraph,
+) -> CliffordCircuit {
+    let mut output = CliffordCircuit::new(circuit.nqbits);
+    for gate in circuit.gates.iter() {
+        match gate {
+            CliffordGate::CNOT(i, j)
+                if !hardware.is_native(*i, *j) && hardware.is_native(*j, *i) =>
+            {
+                output.gates.push(CliffordGate::H(*i));
+                output.gates.push(CliffordGate::H(*j));
+                output.gates.push(CliffordGate::CNOT(*j, *i));
+                output.gates.push(CliffordGate::H(*i));
+                output.gates.push(CliffordGate::H(*j));
+            }
+            _ => output.gates.push(*gate),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::Tableau;
+
+    #[test]
+    fn test_fix_orientation() {
+        let hardware = HardwareGraph::from_directed_couplings(&[(0, 1), (2, 1), (2, 3), (3, 2)]);
+        for _ in 0..10 {
+            let circuit = CliffordCircuit::random(4, 40);
+            let fixed = fix_cnot_orientation(&circuit, &hardware);
+            for gate in fixed.gates.iter() {
+                if let CliffordGate::CNOT(i, j) = gate {
+                    assert!(hardware.is_native(*i, *j) || !hardware.are_connected(*i, *j));
+                }
+            }
+            assert_eq!(
+                Tableau::from_circuit(&fixed).logicals,
+                Tableau::from_circuit(&circuit).logicals
+            );
+        }
+    }
+}
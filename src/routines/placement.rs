@@ -0,0 +1,203 @@
+//! Initial placement of logical qubits on a `HardwareGraph`.
+//!
+//! A layout maps logical qubits to physical qubits: `layout[l]` is the physical qubit hosting
+//! logical qubit `l`. The layout is chosen greedily from an interaction matrix counting, for each
+//! pair of logical qubits, the number of operators acting on both of them.
+//!
+//! The placed qubits always induce a connected subgraph of the hardware. Any synthesis method
+//! working on a `HardwareGraph` can thus be called on `hardware.induced_subgraph(&layout)`, and
+//! its output mapped back to physical qubits using [to_physical_circuit].
+//!
+//! ```
+//! use rustiq_core::structures::{HardwareGraph, PauliSet};
+//! use rustiq_core::routines::placement::{place_pauli_set, to_physical_circuit};
+//! use rustiq_core::synthesis::pauli_network::pauli_network_on_hardware;
+//! let paulis = PauliSet::from_slice(&["XIX".to_string(), "ZIZ".to_string()]);
+//! // A line 0 - 1 - 2 - 3 - 4
+//! let hardware = HardwareGraph::line(5);
+//! let layout = place_pauli_set(&paulis, &hardware);
+//! let circuit = pauli_network_on_hardware(&paulis, &hardware.induced_subgraph(&layout), true);
+//! let circuit = to_physical_circuit(&circuit, &layout, &hardware);
+//! assert_eq!(circuit.nqbits, 5);
+//! ```
+use crate::structures::{CliffordCircuit, HardwareGraph, IsometryTableau, PauliSet};
+
+fn add_interactions(interactions: &mut [Vec<usize>], pauli_set: &PauliSet) {
+    for i in 0..pauli_set.len() {
+        let support = pauli_set.get_support(i);
+        for a in support.iter() {
+            for b in support.iter() {
+                if a != b {
+                    interactions[*a][*b] += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Computes the interaction matrix of a sequence of Pauli operators
+pub fn pauli_set_interactions(pauli_set: &PauliSet) -> Vec<Vec<usize>> {
+    let mut interactions = vec![vec![0; pauli_set.n]; pauli_set.n];
+    add_interactions(&mut interactions, pauli_set);
+    interactions
+}
+
+/// Computes the interaction matrix of an isometry, using the supports of its logical operators
+/// and of its stabilizers
+pub fn isometry_interactions(isometry: &IsometryTableau) -> Vec<Vec<usize>> {
+    let nqbits = isometry.n + isometry.k;
+    let mut interactions = vec![vec![0; nqbits]; nqbits];
+    add_interactions(&mut interactions, &isometry.logicals);
+    add_interactions(&mut interactions, &isometry.stabilizers);
+    interactions
+}
+
+/// Greedily places logical qubits on a connected hardware graph.
+///
+/// The logical qubit with the largest total interaction is placed on the most central physical
+/// qubit. Then, the logical qubit interacting the most with the placed ones is placed on a free
+/// physical qubit adjacent to the placed region, minimizing the interaction-weighted distance to
+/// the placed qubits.
+pub fn greedy_placement(interactions: &[Vec<usize>], hardware: &HardwareGraph) -> Vec<usize> {
+    let nlogical = interactions.len();
+    let nphysical = hardware.len();
+    assert!(
+        nlogical <= nphysical,
+        "The hardware graph should have at least as many qubits as the input"
+    );
+    let mut layout = vec![usize::MAX; nlogical];
+    if nlogical == 0 {
+        return layout;
+    }
+    let mut free = vec![true; nphysical];
+    let mut placed: Vec<usize> = Vec::new();
+    let total_distance = |p: usize, others: &[usize]| -> usize {
+        others.iter().map(|q| hardware.distance(p, *q)).sum()
+    };
+    let all_physical: Vec<usize> = (0..nphysical).collect();
+    let first = (0..nlogical)
+        .max_by_key(|l| (interactions[*l].iter().sum::<usize>(), nlogical - l))
+        .unwrap();
+    let center = (0..nphysical)
+        .min_by_key(|p| total_distance(*p, &all_physical))
+        .unwrap();
+    layout[first] = center;
+    free[center] = false;
+    placed.push(first);
+    while placed.len() < nlogical {
+        let logical = (0..nlogical)
+            .filter(|l| layout[*l] == usize::MAX)
+            .max_by_key(|l| {
+                (
+                    placed.iter().map(|m| interactions[*l][*m]).sum::<usize>(),
+                    nlogical - l,
+                )
+            })
+            .unwrap();
+        let placed_physical: Vec<usize> = placed.iter().map(|m| layout[*m]).collect();
+        let physical = (0..nphysical)
+            .filter(|p| {
+                free[*p]
+                    && placed_physical
+                        .iter()
+                        .any(|q| hardware.are_connected(*p, *q))
+            })
+            .min_by_key(|p| {
+                let weighted: usize = placed
+                    .iter()
+                    .map(|m| interactions[logical][*m] * hardware.distance(*p, layout[*m]))
+                    .sum();
+                (weighted, total_distance(*p, &placed_physical), *p)
+            })
+            .expect("The hardware graph should be connected");
+        layout[logical] = physical;
+        free[physical] = false;
+        placed.push(logical);
+    }
+    layout
+}
+
+/// Chooses a layout for the synthesis of a sequence of Pauli operators
+pub fn place_pauli_set(pauli_set: &PauliSet, hardware: &HardwareGraph) -> Vec<usize> {
+    greedy_placement(&pauli_set_interactions(pauli_set), hardware)
+}
+
+/// Chooses a layout for the synthesis of an isometry
+pub fn place_isometry(isometry: &IsometryTableau, hardware: &HardwareGraph) -> Vec<usize> {
+    greedy_placement(&isometry_interactions(isometry), hardware)
+}
+
+/// Maps a circuit synthesized on `hardware.induced_subgraph(layout)` to the physical qubits of
+/// `hardware`
+pub fn to_physical_circuit(
+    circuit: &CliffordCircuit,
+    layout: &[usize],
+    hardware: &HardwareGraph,
+) -> CliffordCircuit {
+    let mut physical = circuit.relabel(layout);
+    physical.nqbits = hardware.len();
+    physical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::{CliffordGate, Metric, PauliLike};
+    use crate::synthesis::clifford::isometry::isometry_synthesis_on_hardware;
+    use petgraph::algo::connected_components;
+
+    fn check_layout(layout: &[usize], hardware: &HardwareGraph) {
+        let mut sorted = layout.to_vec();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), layout.len());
+        assert!(sorted.iter().all(|p| *p < hardware.len()));
+        assert_eq!(
+            connected_components(&hardware.induced_subgraph(layout).graph),
+            1
+        );
+    }
+
+    #[test]
+    fn test_pauli_set_placement() {
+        let hardware = HardwareGraph::grid(3, 3);
+        let paulis = PauliSet::from_slice(&[
+            "XIIZ".to_string(),
+            "ZIIX".to_string(),
+            "IXIZ".to_string(),
+            "IIXI".to_string(),
+        ]);
+        let layout = place_pauli_set(&paulis, &hardware);
+        check_layout(&layout, &hardware);
+        assert_eq!(layout[3], 4);
+        assert_eq!(hardware.distance(layout[0], layout[3]), 1);
+        assert_eq!(hardware.distance(layout[1], layout[3]), 1);
+    }
+
+    #[test]
+    fn test_isometry_placement() {
+        let hardware = HardwareGraph::grid(3, 3);
+        for _ in 0..10 {
+            let mut isometry = IsometryTableau::random(2, 3);
+            let layout = place_isometry(&isometry, &hardware);
+            check_layout(&layout, &hardware);
+            let circuit = isometry_synthesis_on_hardware(
+                &isometry,
+                &hardware.induced_subgraph(&layout),
+                &Metric::COUNT,
+                1,
+            );
+            let physical = to_physical_circuit(&circuit, &layout, &hardware);
+            for gate in physical.gates.iter() {
+                if let CliffordGate::CNOT(i, j) | CliffordGate::CZ(i, j) = gate {
+                    assert!(hardware.are_connected(*i, *j));
+                }
+            }
+            isometry.normalize_inplace();
+            let mut simulated = IsometryTableau::new(2, 3);
+            simulated.conjugate_with_circuit(&circuit);
+            simulated.normalize_inplace();
+            assert_eq!(simulated, isometry);
+        }
+    }
+}
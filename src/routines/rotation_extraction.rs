@@ -1,5 +1,5 @@
 /// A simple function that expresses a given circuit as a sequence of Pauli rotations
-/// followed by a final Clifford operator
+/// followed by a final Clifford operator (see also [Circuit::to_rotations](crate::structures::Circuit::to_rotations))
 use crate::structures::{PauliLike, Tableau};
 
 pub fn extract_rotations(
@@ -10,7 +10,7 @@ pub fn extract_rotations(
     let mut rotations = Vec::new();
     for (gate_name, qbits) in circuit.iter() {
         match gate_name.as_str() {
-            "CX" => clifford.cnot(qbits[0], qbits[1]),
+            "CX" | "CNOT" => clifford.cnot(qbits[0], qbits[1]),
             "CZ" => clifford.cz(qbits[0], qbits[1]),
             "H" => clifford.h(qbits[0]),
             "S" => clifford.s(qbits[0]),
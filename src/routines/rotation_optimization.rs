@@ -4,6 +4,8 @@ use crate::structures::pauli_dag::{build_dag_from_pauli_set, get_front_layer, Da
 use crate::structures::{CliffordCircuit, CliffordGate, Parameter, PauliLike, PauliSet, Tableau};
 use std::collections::HashSet;
 
+/// Conjugates `rest` by the rotation of angle `k * pi/2` around `axis` (or by its inverse if
+/// `dagger` is set). Only the S gates depend on `dagger`: the basis change is the same both ways.
 fn update_rot_pi2<T: PauliLike>(axis: &str, k: i32, rest: &mut T, dagger: bool) {
     let support: Vec<_> = (0..axis.len())
         .filter(|i| axis.chars().nth(*i).unwrap() != 'I')
@@ -11,13 +13,7 @@ fn update_rot_pi2<T: PauliLike>(axis: &str, k: i32, rest: &mut T, dagger: bool)
     for qbit in support.iter() {
         match axis.chars().nth(*qbit).unwrap() {
             'X' => rest.h(*qbit),
-            'Y' => {
-                if dagger {
-                    rest.sqrt_xd(*qbit)
-                } else {
-                    rest.sqrt_x(*qbit)
-                }
-            }
+            'Y' => rest.sqrt_x(*qbit),
             _ => {}
         }
     }
@@ -38,13 +34,7 @@ fn update_rot_pi2<T: PauliLike>(axis: &str, k: i32, rest: &mut T, dagger: bool)
     for qbit in support.iter() {
         match axis.chars().nth(*qbit).unwrap() {
             'X' => rest.h(*qbit),
-            'Y' => {
-                if dagger {
-                    rest.sqrt_x(*qbit)
-                } else {
-                    rest.sqrt_xd(*qbit)
-                }
-            }
+            'Y' => rest.sqrt_xd(*qbit),
             _ => {}
         }
     }
@@ -309,6 +299,12 @@ impl MarkedPauliDag {
     }
 }
 
+/// Propagates the |0...0> initial state through the rotations, as long as the successive passes
+/// over the anti-commutation DAG simplify them.
+///
+/// A pass can increase the support of the rotations that follow the simplified ones, so that
+/// successive passes may cycle. Since passes are deterministic, we stop as soon as a pass leads
+/// back to an already visited set of rotations.
 pub fn full_initial_state_propagation(
     rotations: &[(String, Parameter)],
 ) -> (Vec<(String, Parameter)>, Tableau) {
@@ -316,6 +312,7 @@ pub fn full_initial_state_propagation(
     let mut angles: Vec<_> = rotations.iter().map(|e| e.1.clone()).collect();
     let mut pset = PauliSet::from_slice(&axes);
     let mut final_clifford = Tableau::new(pset.n);
+    let mut visited = HashSet::new();
     loop {
         let mpdag = MarkedPauliDag::new(pset);
         let (new_pset, clifford, new_rotations, carry_on) = mpdag.propagate();
@@ -325,7 +322,7 @@ pub fn full_initial_state_propagation(
             .collect();
         pset = new_pset;
         final_clifford = final_clifford * clifford;
-        if !carry_on {
+        if !carry_on || !visited.insert(pset.clone()) {
             break;
         }
     }
@@ -339,3 +336,34 @@ pub fn full_initial_state_propagation(
     }
     (new_rotations, final_clifford)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_rot_pi2_dagger_is_inverse() {
+        let operators = ["XIZ", "IYI", "ZZY", "YXX", "IIZ"].map(String::from);
+        for axis in ["YII", "XYI", "IYY", "ZYX", "XZI"] {
+            for k in 1..4 {
+                let mut rest = PauliSet::from_slice(&operators);
+                let expected = rest.clone();
+                update_rot_pi2(axis, k, &mut rest, false);
+                update_rot_pi2(axis, k, &mut rest, true);
+                assert_eq!(rest, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_full_initial_state_propagation_terminates() {
+        // Successive passes on these rotations cycle
+        let rotations: Vec<(String, Parameter)> = ["XX", "XI", "IX"]
+            .iter()
+            .zip([0.1, 0.2, 0.3])
+            .map(|(axis, angle)| (axis.to_string(), Parameter::Concrete(angle)))
+            .collect();
+        let (new_rotations, _) = full_initial_state_propagation(&rotations);
+        assert_eq!(new_rotations.len(), rotations.len());
+    }
+}
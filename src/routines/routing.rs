@@ -0,0 +1,205 @@
+//! A SWAP-based routing pass mapping a `CliffordCircuit` onto a `HardwareGraph`.
+//!
+//! Layouts map logical qubits to physical qubits: `layout[l]` is the physical qubit currently
+//! holding logical qubit `l`.
+use crate::structures::{CliffordCircuit, CliffordGate, HardwareGraph};
+
+/// Completes a partial layout with the unused physical qubits
+fn complete_layout(partial: &[usize], nphysical: usize) -> Vec<usize> {
+    let mut layout = partial.to_vec();
+    let mut used = vec![false; nphysical];
+    for p in partial.iter() {
+        assert!(
+            *p < nphysical,
+            "Physical qubit {} is not in the hardware",
+            p
+        );
+        assert!(
+            !used[*p],
+            "Physical qubit {} appears twice in the layout",
+            p
+        );
+        used[*p] = true;
+    }
+    layout.extend((0..nphysical).filter(|p| !used[*p]));
+    layout
+}
+
+/// Appends a SWAP gate, decomposed into 3 CNOT gates
+fn push_swap(circuit: &mut CliffordCircuit, i: usize, j: usize) {
+    circuit.gates.push(CliffordGate::CNOT(i, j));
+    circuit.gates.push(CliffordGate::CNOT(j, i));
+    circuit.gates.push(CliffordGate::CNOT(i, j));
+}
+
+/// Computes the path along which a two-qubit gate acting on physical qubits `i` and `j` is
+/// routed: all the couplings of the path but the last one are used by SWAP gates.
+///
+/// This is the cheapest path between the two qubits, unless they are coupled and applying the
+/// gate directly is cheaper than swapping along the path.
+fn cheapest_path(hardware: &HardwareGraph, i: usize, j: usize) -> Vec<usize> {
+    let path = hardware.shortest_path(i, j);
+    if let Some(direct_cost) = hardware.coupling_cost(i, j) {
+        let last_cost = hardware.coupling_cost(path[path.len() - 2], j).unwrap();
+        let swaps_cost = 3. * (hardware.path_cost(i, j) - last_cost) + last_cost;
+        if direct_cost <= swaps_cost {
+            return vec![i, j];
+        }
+    }
+    path
+}
+
+/// Routes a circuit on a hardware graph by inserting SWAP gates.
+///
+/// Before each two-qubit gate acting on physical qubits that are not coupled, the first qubit is
+/// moved along a cheapest path until it is coupled to the second one. SWAP gates are decomposed
+/// into 3 CNOT gates acting on coupled qubits. When coupling costs are provided, a gate acting on
+/// coupled qubits can also be routed through cheaper couplings.
+///
+/// `initial_layout` maps the logical qubits of the circuit to physical qubits, and defaults to the
+/// identity. The unused physical qubits are appended to it as idle logical qubits.
+///
+/// Returns the routed circuit, acting on `hardware.len()` qubits, together with the initial and
+/// final layouts.
+pub fn route_circuit(
+    circuit: &CliffordCircuit,
+    hardware: &HardwareGraph,
+    initial_layout: Option<&[usize]>,
+) -> (CliffordCircuit, Vec<usize>, Vec<usize>) {
+    let nphysical = hardware.len();
+    assert!(
+        circuit.nqbits <= nphysical,
+        "The circuit has more qubits than the hardware"
+    );
+    let initial_layout = match initial_layout {
+        Some(layout) => {
+            assert_eq!(
+                layout.len(),
+                circuit.nqbits,
+                "The layout should map all the qubits of the circuit"
+            );
+            complete_layout(layout, nphysical)
+        }
+        None => (0..nphysical).collect(),
+    };
+    let mut layout = initial_layout.clone();
+    let mut logical_of = vec![0; nphysical];
+    for (l, p) in layout.iter().enumerate() {
+        logical_of[*p] = l;
+    }
+    let mut routed = CliffordCircuit::new(nphysical);
+    for gate in circuit.gates.iter() {
+        if let CliffordGate::CNOT(a, b) | CliffordGate::CZ(a, b) = gate {
+            let path = cheapest_path(hardware, layout[*a], layout[*b]);
+            for step in path.windows(2).take(path.len().saturating_sub(2)) {
+                push_swap(&mut routed, step[0], step[1]);
+                let (la, lb) = (logical_of[step[0]], logical_of[step[1]]);
+                logical_of.swap(step[0], step[1]);
+                layout.swap(la, lb);
+            }
+        }
+        routed.gates.push(match gate {
+            CliffordGate::CNOT(a, b) => CliffordGate::CNOT(layout[*a], layout[*b]),
+            CliffordGate::CZ(a, b) => CliffordGate::CZ(layout[*a], layout[*b]),
+            CliffordGate::H(a) => CliffordGate::H(layout[*a]),
+            CliffordGate::S(a) => CliffordGate::S(layout[*a]),
+            CliffordGate::Sd(a) => CliffordGate::Sd(layout[*a]),
+            CliffordGate::SqrtX(a) => CliffordGate::SqrtX(layout[*a]),
+            CliffordGate::SqrtXd(a) => CliffordGate::SqrtXd(layout[*a]),
+        });
+    }
+    (routed, initial_layout, layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::{PauliLike, PauliSet};
+
+    /// Checks that `routed` implements `circuit` with logical qubit `l` starting on physical qubit
+    /// `initial[l]` and ending on physical qubit `final_layout[l]`.
+    fn check_routing(
+        circuit: &CliffordCircuit,
+        hardware: &HardwareGraph,
+        routed: &CliffordCircuit,
+        initial: &[usize],
+        final_layout: &[usize],
+    ) {
+        for gate in routed.gates.iter() {
+            if let CliffordGate::CNOT(i, j) | CliffordGate::CZ(i, j) = gate {
+                assert!(hardware.are_connected(*i, *j));
+            }
+        }
+        let n = hardware.len();
+        let mut physical = PauliSet::new(n);
+        let mut logical = PauliSet::new(n);
+        for l in 0..n {
+            for offset in [0, n] {
+                let mut vec = vec![false; 2 * n];
+                vec[offset + initial[l]] = true;
+                physical.insert_vec_bool(&vec, false);
+                let mut vec = vec![false; 2 * n];
+                vec[offset + l] = true;
+                logical.insert_vec_bool(&vec, false);
+            }
+        }
+        physical.conjugate_with_circuit(routed);
+        logical.conjugate_with_circuit(circuit);
+        for i in 0..2 * n {
+            let (phase, string) = logical.get(i);
+            let mut expected = vec!['I'; n];
+            for (l, c) in string.chars().enumerate() {
+                expected[final_layout[l]] = c;
+            }
+            let expected: String = expected.into_iter().collect();
+            assert_eq!(physical.get(i), (phase, expected));
+        }
+    }
+
+    #[test]
+    fn test_route_line() {
+        let hardware = HardwareGraph::line(5);
+        for _ in 0..10 {
+            let circuit = CliffordCircuit::random(5, 50);
+            let (routed, initial, final_layout) = route_circuit(&circuit, &hardware, None);
+            check_routing(&circuit, &hardware, &routed, &initial, &final_layout);
+        }
+    }
+
+    #[test]
+    fn test_route_with_layout() {
+        let hardware = HardwareGraph::grid(2, 3);
+        for _ in 0..10 {
+            let circuit = CliffordCircuit::random(4, 50);
+            let (routed, initial, final_layout) =
+                route_circuit(&circuit, &hardware, Some(&[5, 0, 2, 3]));
+            assert_eq!(initial[..4], [5, 0, 2, 3]);
+            check_routing(&circuit, &hardware, &routed, &initial, &final_layout);
+        }
+    }
+
+    #[test]
+    fn test_avoid_expensive_coupling() {
+        let mut circuit = CliffordCircuit::new(3);
+        circuit.gates.push(CliffordGate::CNOT(0, 2));
+        // A SWAP along 0 - 1 plus a CNOT on 1 - 2 costs 4
+        for (cost, nswaps) in [(10., 1), (3., 0)] {
+            let hardware =
+                HardwareGraph::from_weighted_couplings(&[(0, 1, 1.), (1, 2, 1.), (0, 2, cost)]);
+            let (routed, initial, final_layout) = route_circuit(&circuit, &hardware, None);
+            assert_eq!(routed.gates.len(), 3 * nswaps + 1);
+            check_routing(&circuit, &hardware, &routed, &initial, &final_layout);
+        }
+    }
+
+    #[test]
+    fn test_no_swap_needed() {
+        let hardware = HardwareGraph::line(3);
+        let mut circuit = CliffordCircuit::new(3);
+        circuit.gates.push(CliffordGate::CNOT(0, 1));
+        circuit.gates.push(CliffordGate::CZ(2, 1));
+        let (routed, initial, final_layout) = route_circuit(&circuit, &hardware, None);
+        assert_eq!(routed.gates, circuit.gates);
+        assert_eq!(initial, final_layout);
+    }
+}
@@ -0,0 +1,223 @@
+//! Reduction of a single Pauli operator along a tree of coupled qubits.
+//!
+//! These helpers are used by the hardware-aware synthesis methods: the support of an operator is
+//! folded onto a single qubit using only CNOT gates acting on the edges of a tree (typically a
+//! Steiner tree of some `HardwareGraph`). The qubits are typically freed one by one, following an
+//! [elimination_order] of the hardware graph, and [best_elimination] tries several such orders.
+use crate::structures::{CliffordCircuit, CliffordGate, HardwareGraph, Metric, PauliSet};
+use petgraph::visit::Bfs;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use std::collections::VecDeque;
+
+/// The single-qubit Pauli on which an operator is folded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FoldBasis {
+    X,
+    Z,
+}
+
+/// Orders the nodes of a tree (given as a list of edges) in BFS order starting from `root`.
+/// Returns the ordered nodes together with the parent of each of them (the root has no parent).
+pub fn bfs_order(edges: &[(usize, usize)], root: usize) -> Vec<(usize, Option<usize>)> {
+    let mut order = vec![(root, None)];
+    let mut queue = VecDeque::from([root]);
+    let mut visited = vec![root];
+    while let Some(node) = queue.pop_front() {
+        for (a, b) in edges.iter() {
+            let neighbor = if *a == node {
+                *b
+            } else if *b == node {
+                *a
+            } else {
+                continue;
+            };
+            if !visited.contains(&neighbor) {
+                visited.push(neighbor);
+                order.push((neighbor, Some(node)));
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    order
+}
+
+/// Computes an elimination order of the qubits of a connected hardware graph.
+///
+/// This is the reverse of a BFS order starting from `root`: removing the qubits one by one in
+/// this order always leaves a connected subgraph.
+pub fn elimination_order(hardware: &HardwareGraph, root: usize) -> Vec<usize> {
+    let mut order = Vec::new();
+    let mut bfs = Bfs::new(&hardware.graph, petgraph::graph::NodeIndex::new(root));
+    while let Some(node) = bfs.next(&hardware.graph) {
+        order.push(node.index());
+    }
+    order.reverse();
+    order
+}
+
+/// Runs `reduce` on the elimination orders rooted at up to `niter` random qubits, and returns the
+/// best circuit according to `metric`.
+pub fn best_elimination<F>(
+    hardware: &HardwareGraph,
+    metric: &Metric,
+    niter: usize,
+    mut reduce: F,
+) -> CliffordCircuit
+where
+    F: FnMut(&[usize]) -> CliffordCircuit,
+{
+    let mut roots: Vec<usize> = (0..hardware.len()).collect();
+    roots.shuffle(&mut thread_rng());
+    let mut best_circuit = None;
+    let mut best_cost = usize::MAX;
+    for root in roots.iter().take(niter.max(1)) {
+        let circuit = reduce(&elimination_order(hardware, *root));
+        let cost = metric.on_circuit(&circuit);
+        if cost < best_cost {
+            best_cost = cost;
+            best_circuit = Some(circuit);
+        }
+    }
+    best_circuit.unwrap()
+}
+
+/// Adds `qbit` to a sorted list of terminals
+pub fn with_terminal(mut terminals: Vec<usize>, qbit: usize) -> Vec<usize> {
+    if !terminals.contains(&qbit) {
+        terminals.push(qbit);
+        terminals.sort();
+    }
+    terminals
+}
+
+/// Computes the Steiner trees of several candidate terminal sets within the qubits in `nodes`
+/// (see [steiner_edges_within]). Returns the label and the edges of the cheapest tree, if any.
+pub fn cheapest_steiner_edges<T>(
+    hardware: &HardwareGraph,
+    nodes: &[usize],
+    candidates: impl IntoIterator<Item = (T, Vec<usize>)>,
+) -> Option<(T, Vec<(usize, usize)>)> {
+    candidates
+        .into_iter()
+        .map(|(label, terminals)| (label, steiner_edges_within(hardware, nodes, &terminals)))
+        .min_by(|(_, a), (_, b)| hardware.edges_cost(a).total_cmp(&hardware.edges_cost(b)))
+}
+
+/// Computes the edges of a Steiner tree of `terminals` in the subgraph of `hardware` induced by
+/// the qubits in `nodes`. The edges are labeled by qubits of the original graph.
+pub fn steiner_edges_within(
+    hardware: &HardwareGraph,
+    nodes: &[usize],
+    terminals: &[usize],
+) -> Vec<(usize, usize)> {
+    if terminals.len() < 2 {
+        return Vec::new();
+    }
+    let subgraph = hardware.induced_subgraph(nodes);
+    let sub_terminals: Vec<usize> = terminals
+        .iter()
+        .map(|t| nodes.iter().position(|node| node == t).unwrap())
+        .collect();
+    subgraph
+        .get_steiner_tree(&sub_terminals)
+        .edges()
+        .iter()
+        .map(|(a, b)| (nodes[*a], nodes[*b]))
+        .collect()
+}
+
+/// Computes a circuit that maps operator `index` of `pauli_set` onto a single-qubit Pauli (X or Z
+/// depending on `basis`) acting on `root`.
+///
+/// The circuit starts with a layer of single-qubit gates rotating the operator into the target
+/// basis. All CNOT gates then act on edges of the tree. Nodes of the tree on which the operator
+/// acts trivially are used as Steiner nodes.
+///
+/// When folding in the X basis an operator acting as X or Y on `root`, the root is only ever used
+/// as a CNOT control and is only rotated by S/Sd gates. As a consequence, an operator equal to Z
+/// on `root` is left untouched.
+pub fn fold_along_tree(
+    pauli_set: &PauliSet,
+    index: usize,
+    edges: &[(usize, usize)],
+    root: usize,
+    basis: FoldBasis,
+) -> CliffordCircuit {
+    let n = pauli_set.n;
+    let mut circuit = CliffordCircuit::new(n);
+    let order = bfs_order(edges, root);
+    let mut active = vec![false; n];
+    for (node, _) in order.iter() {
+        let x = pauli_set.get_entry(*node, index);
+        let z = pauli_set.get_entry(*node + n, index);
+        match (basis, x, z) {
+            (_, false, false) => continue,
+            (FoldBasis::Z, true, false) => circuit.gates.push(CliffordGate::H(*node)),
+            (FoldBasis::Z, true, true) => circuit.gates.push(CliffordGate::SqrtX(*node)),
+            (FoldBasis::X, false, true) => circuit.gates.push(CliffordGate::H(*node)),
+            (FoldBasis::X, true, true) => circuit.gates.push(CliffordGate::Sd(*node)),
+            _ => {}
+        }
+        active[*node] = true;
+    }
+    for (node, parent) in order.iter().rev() {
+        if let Some(parent) = parent {
+            if !active[*node] {
+                continue;
+            }
+            let (fold, spread) = match basis {
+                FoldBasis::Z => (
+                    CliffordGate::CNOT(*node, *parent),
+                    CliffordGate::CNOT(*parent, *node),
+                ),
+                FoldBasis::X => (
+                    CliffordGate::CNOT(*parent, *node),
+                    CliffordGate::CNOT(*node, *parent),
+                ),
+            };
+            if !active[*parent] {
+                circuit.gates.push(spread);
+                active[*parent] = true;
+            }
+            circuit.gates.push(fold);
+            active[*node] = false;
+        }
+    }
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::PauliLike;
+
+    #[test]
+    fn test_fold_z() {
+        // A path 0 - 1 - 2 - 3 - 4, with qubit 1 used as a Steiner node
+        let edges = [(0, 1), (1, 2), (2, 3), (3, 4)];
+        let mut pset = PauliSet::new(5);
+        pset.insert("XIYZZ", false);
+        let circuit = fold_along_tree(&pset, 0, &edges, 3, FoldBasis::Z);
+        for gate in circuit.gates.iter() {
+            if let CliffordGate::CNOT(i, j) = gate {
+                assert!(edges.contains(&(*i, *j)) || edges.contains(&(*j, *i)));
+            }
+        }
+        pset.conjugate_with_circuit(&circuit);
+        assert_eq!(pset.get(0).1, "IIIZI");
+        assert_eq!(circuit.cnot_count(), 5);
+    }
+
+    #[test]
+    fn test_fold_x_preserves_root_z() {
+        let edges = [(0, 1), (0, 2), (2, 3)];
+        let mut pset = PauliSet::new(4);
+        pset.insert("YZIX", false);
+        pset.insert("ZIII", false);
+        let circuit = fold_along_tree(&pset, 0, &edges, 0, FoldBasis::X);
+        pset.conjugate_with_circuit(&circuit);
+        assert_eq!(pset.get(0).1, "XIII");
+        assert_eq!(pset.get(1).1, "ZIII");
+    }
+}
@@ -0,0 +1,76 @@
+//! A minimal complex number type used by the simulators
+use std::ops;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub const ZERO: Self = Self { re: 0., im: 0. };
+    pub const ONE: Self = Self { re: 1., im: 0. };
+    pub const I: Self = Self { re: 0., im: 1. };
+
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+    /// Returns exp(i theta)
+    pub fn from_angle(theta: f64) -> Self {
+        Self::new(theta.cos(), theta.sin())
+    }
+    pub fn conj(&self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+    pub fn norm_sqr(&self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+    pub fn norm(&self) -> f64 {
+        self.norm_sqr().sqrt()
+    }
+}
+
+impl ops::Add for Complex {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl ops::Sub for Complex {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl ops::Neg for Complex {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+impl ops::Mul for Complex {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl ops::Mul<f64> for Complex {
+    type Output = Self;
+    fn mul(self, other: f64) -> Self {
+        Self::new(self.re * other, self.im * other)
+    }
+}
+
+impl ops::Div<f64> for Complex {
+    type Output = Self;
+    fn div(self, other: f64) -> Self {
+        Self::new(self.re / other, self.im / other)
+    }
+}
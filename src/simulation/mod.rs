@@ -0,0 +1,10 @@
+//! This module contains dense simulators used to verify small circuits
+//!
+//! * [StateVector] - a dense state vector supporting all the gates of [Circuit](crate::structures::Circuit)
+//! * [circuit_unitary] - the unitary matrix implemented by a circuit
+//! * [circuits_equivalent] - equivalence check of two circuits up to a global phase
+pub mod complex;
+pub mod statevector;
+
+pub use complex::Complex;
+pub use statevector::{circuit_unitary, circuits_equivalent, StateVector, MAX_QUBITS};
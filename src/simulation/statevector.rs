@@ -0,0 +1,394 @@
+//! Dense state vector simulation.
+//!
+//! Qubit `q` is bit `q` of the index of a basis state, i.e. amplitude `i` corresponds to
+//! |b_{n-1} ... b_1 b_0> with `i = sum_q b_q 2^q`. Gates follow the conventions of the
+//! [Tableau](crate::structures::Tableau) data structure: SqrtX = exp(-i pi/4 X) and rotations are
+//! R_P(theta) = exp(-i theta P / 2).
+//!
+//! ```
+//! use rustiq_core::simulation::{circuits_equivalent, StateVector};
+//! use rustiq_core::structures::{Circuit, Parameter};
+//! let gates = vec![("H".to_owned(), vec![0]), ("CX".to_owned(), vec![0, 1])];
+//! let bell = Circuit::from_vec(&gates, &[]).unwrap();
+//! let mut state = StateVector::new(2);
+//! state.apply_circuit(&bell);
+//! assert!((state.amplitudes[3].re - 0.5f64.sqrt()).abs() < 1e-12);
+//! // RZZ(theta) = CX RZ(theta) CX
+//! let rzz = Circuit::from_vec(&[("RZZ".to_owned(), vec![0, 1])], &[Parameter::Concrete(0.3)]);
+//! let gates = vec![
+//!     ("CX".to_owned(), vec![0, 1]),
+//!     ("RZ".to_owned(), vec![1]),
+//!     ("CX".to_owned(), vec![0, 1]),
+//! ];
+//! let decomposed = Circuit::from_vec(&gates, &[Parameter::Concrete(0.3)]);
+//! assert!(circuits_equivalent(&rzz.unwrap(), &decomposed.unwrap()));
+//! ```
+use super::Complex;
+use crate::structures::{Circuit, CliffordCircuit, CliffordGate, Gate, Parameter};
+use std::f64::consts::FRAC_1_SQRT_2;
+
+/// Largest number of qubits a [StateVector] can hold
+pub const MAX_QUBITS: usize = 20;
+
+/// Tolerance used when comparing amplitudes
+const TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateVector {
+    pub nqbits: usize,
+    pub amplitudes: Vec<Complex>,
+}
+
+fn concrete(angle: &Parameter) -> f64 {
+    match angle {
+        Parameter::Concrete(theta) => *theta,
+        Parameter::Abstract(expr) => panic!("Cannot simulate abstract parameter `{}`", expr),
+    }
+}
+
+impl StateVector {
+    /// Allocates the |0...0> state over `nqbits` qubits
+    pub fn new(nqbits: usize) -> Self {
+        Self::basis_state(nqbits, 0)
+    }
+    /// Allocates the computational basis state of index `index`
+    pub fn basis_state(nqbits: usize, index: usize) -> Self {
+        assert!(
+            nqbits <= MAX_QUBITS,
+            "Cannot simulate more than {} qubits",
+            MAX_QUBITS
+        );
+        assert!(index < 1 << nqbits, "Basis state {} is out of range", index);
+        let mut amplitudes = vec![Complex::ZERO; 1 << nqbits];
+        amplitudes[index] = Complex::ONE;
+        Self { nqbits, amplitudes }
+    }
+
+    /// Applies a single qubit gate given as a 2x2 matrix
+    fn apply_matrix(&mut self, qbit: usize, m: [[Complex; 2]; 2]) {
+        let mask = 1 << qbit;
+        for i in 0..self.amplitudes.len() {
+            if i & mask == 0 {
+                let (a, b) = (self.amplitudes[i], self.amplitudes[i | mask]);
+                self.amplitudes[i] = m[0][0] * a + m[0][1] * b;
+                self.amplitudes[i | mask] = m[1][0] * a + m[1][1] * b;
+            }
+        }
+    }
+
+    /// Applies a Clifford gate
+    pub fn apply_clifford_gate(&mut self, gate: &CliffordGate) {
+        let h = Complex::new(FRAC_1_SQRT_2, 0.);
+        let ih = Complex::new(0., FRAC_1_SQRT_2);
+        match *gate {
+            CliffordGate::CNOT(control, target) => {
+                for i in 0..self.amplitudes.len() {
+                    if (i >> control) & 1 == 1 && (i >> target) & 1 == 0 {
+                        self.amplitudes.swap(i, i | (1 << target));
+                    }
+                }
+            }
+            CliffordGate::CZ(a, b) => {
+                for (i, amplitude) in self.amplitudes.iter_mut().enumerate() {
+                    if (i >> a) & 1 == 1 && (i >> b) & 1 == 1 {
+                        *amplitude = -*amplitude;
+                    }
+                }
+            }
+            CliffordGate::H(q) => self.apply_matrix(q, [[h, h], [h, -h]]),
+            CliffordGate::S(q) => self.apply_matrix(
+                q,
+                [[Complex::ONE, Complex::ZERO], [Complex::ZERO, Complex::I]],
+            ),
+            CliffordGate::Sd(q) => self.apply_matrix(
+                q,
+                [[Complex::ONE, Complex::ZERO], [Complex::ZERO, -Complex::I]],
+            ),
+            CliffordGate::SqrtX(q) => self.apply_matrix(q, [[h, -ih], [-ih, h]]),
+            CliffordGate::SqrtXd(q) => self.apply_matrix(q, [[h, ih], [ih, h]]),
+        }
+    }
+
+    /// Applies a Clifford circuit
+    pub fn apply_clifford_circuit(&mut self, circuit: &CliffordCircuit) {
+        for gate in circuit.gates.iter() {
+            self.apply_clifford_gate(gate);
+        }
+    }
+
+    /// Applies a Pauli operator given as a pair (phase, string), a true phase meaning a -1 factor
+    pub fn apply_pauli(&mut self, phase: bool, pauli: &str) {
+        let mut x_mask = 0;
+        let mut z_mask = 0;
+        let mut y_count = 0;
+        for (q, c) in pauli.chars().enumerate() {
+            match c {
+                'X' => x_mask |= 1 << q,
+                'Z' => z_mask |= 1 << q,
+                'Y' => {
+                    x_mask |= 1 << q;
+                    z_mask |= 1 << q;
+                    y_count += 1;
+                }
+                'I' => {}
+                _ => panic!("Invalid Pauli operator {}", pauli),
+            }
+        }
+        // Y = i X Z
+        let mut factor = [Complex::ONE, Complex::I, -Complex::ONE, -Complex::I][y_count % 4];
+        if phase {
+            factor = -factor;
+        }
+        let mut output = vec![Complex::ZERO; self.amplitudes.len()];
+        for (i, amplitude) in self.amplitudes.iter().enumerate() {
+            let sign = if (i & z_mask).count_ones() % 2 == 1 {
+                -1.
+            } else {
+                1.
+            };
+            output[i ^ x_mask] = factor * *amplitude * sign;
+        }
+        self.amplitudes = output;
+    }
+
+    /// Applies the rotation exp(-i theta P / 2)
+    pub fn apply_pauli_rotation(&mut self, pauli: &str, theta: f64) {
+        let mut rotated = self.clone();
+        rotated.apply_pauli(false, pauli);
+        let (c, s) = ((theta / 2.).cos(), (theta / 2.).sin());
+        let minus_i_s = Complex::new(0., -s);
+        for (amplitude, rotated) in self.amplitudes.iter_mut().zip(rotated.amplitudes.iter()) {
+            *amplitude = *amplitude * c + minus_i_s * *rotated;
+        }
+    }
+
+    /// Applies a gate. Panics if the gate carries an abstract parameter.
+    pub fn apply_gate(&mut self, gate: &Gate) {
+        let single_qubit_axis = |qbit: usize, axis: char| -> String {
+            (0..self.nqbits)
+                .map(|q| if q == qbit { axis } else { 'I' })
+                .collect()
+        };
+        match gate {
+            Gate::Clifford(gate) => self.apply_clifford_gate(gate),
+            Gate::Rz(q, angle) => {
+                let theta = concrete(angle);
+                let (a, b) = (
+                    Complex::from_angle(-theta / 2.),
+                    Complex::from_angle(theta / 2.),
+                );
+                self.apply_matrix(*q, [[a, Complex::ZERO], [Complex::ZERO, b]]);
+            }
+            Gate::Rx(q, angle) => {
+                self.apply_pauli_rotation(&single_qubit_axis(*q, 'X'), concrete(angle))
+            }
+            Gate::Ry(q, angle) => {
+                self.apply_pauli_rotation(&single_qubit_axis(*q, 'Y'), concrete(angle))
+            }
+            Gate::PauliRotation(axis, angle) => {
+                assert!(
+                    axis.len() <= self.nqbits,
+                    "Rotation axis {} is too large",
+                    axis
+                );
+                self.apply_pauli_rotation(axis, concrete(angle))
+            }
+        }
+    }
+
+    /// Applies a circuit. Panics if the circuit contains abstract parameters.
+    pub fn apply_circuit(&mut self, circuit: &Circuit) {
+        for gate in circuit.gates.iter() {
+            self.apply_gate(gate);
+        }
+    }
+
+    /// Returns the inner product <self|other>
+    pub fn inner_product(&self, other: &Self) -> Complex {
+        self.amplitudes
+            .iter()
+            .zip(other.amplitudes.iter())
+            .fold(Complex::ZERO, |acc, (a, b)| acc + a.conj() * *b)
+    }
+
+    /// Checks whether two states are equal up to a global phase
+    pub fn equals_up_to_phase(&self, other: &Self) -> bool {
+        self.nqbits == other.nqbits && global_phase(&self.amplitudes, &other.amplitudes).is_some()
+    }
+}
+
+/// Returns the phase `z` such that `a = z b` if it exists
+fn global_phase(a: &[Complex], b: &[Complex]) -> Option<Complex> {
+    let (k, pivot) = b
+        .iter()
+        .enumerate()
+        .max_by(|x, y| x.1.norm_sqr().total_cmp(&y.1.norm_sqr()))?;
+    if pivot.norm() < TOLERANCE {
+        return a
+            .iter()
+            .all(|x| x.norm() < TOLERANCE)
+            .then_some(Complex::ONE);
+    }
+    let phase = a[k] * pivot.conj() / pivot.norm_sqr();
+    let matches = |phase: Complex| {
+        a.iter()
+            .zip(b.iter())
+            .all(|(x, y)| (*x - phase * *y).norm() < TOLERANCE)
+    };
+    ((phase.norm() - 1.).abs() < TOLERANCE && matches(phase)).then_some(phase)
+}
+
+/// Computes the unitary matrix implemented by a circuit, as the list of its columns.
+///
+/// The cost is exponential in the number of qubits: this is meant for circuits over a dozen
+/// qubits at most.
+pub fn circuit_unitary(circuit: &Circuit) -> Vec<Vec<Complex>> {
+    (0..1 << circuit.nqbits)
+        .map(|j| {
+            let mut state = StateVector::basis_state(circuit.nqbits, j);
+            state.apply_circuit(circuit);
+            state.amplitudes
+        })
+        .collect()
+}
+
+/// Checks that two circuits implement the same unitary operator up to a global phase.
+///
+/// The circuits are compared over the largest of their qubit counts. The cost is exponential in
+/// the number of qubits: this is meant for circuits over a dozen qubits at most.
+pub fn circuits_equivalent(circuit1: &Circuit, circuit2: &Circuit) -> bool {
+    let nqbits = circuit1.nqbits.max(circuit2.nqbits);
+    let mut phase: Option<Complex> = None;
+    for j in 0..1 << nqbits {
+        let mut state1 = StateVector::basis_state(nqbits, j);
+        let mut state2 = state1.clone();
+        state1.apply_circuit(circuit1);
+        state2.apply_circuit(circuit2);
+        let column_phase = match global_phase(&state1.amplitudes, &state2.amplitudes) {
+            Some(column_phase) => column_phase,
+            None => return false,
+        };
+        match phase {
+            None => phase = Some(column_phase),
+            Some(phase) if (phase - column_phase).norm() > TOLERANCE => return false,
+            _ => {}
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::{PauliLike, PauliSet, Tableau};
+    use rand::Rng;
+
+    fn random_state(nqbits: usize) -> StateVector {
+        let mut rng = rand::thread_rng();
+        let mut state = StateVector::new(nqbits);
+        for amplitude in state.amplitudes.iter_mut() {
+            *amplitude = Complex::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+        }
+        let norm = state.inner_product(&state).re.sqrt();
+        for amplitude in state.amplitudes.iter_mut() {
+            *amplitude = *amplitude / norm;
+        }
+        state
+    }
+
+    fn random_pauli(nqbits: usize) -> String {
+        let mut rng = rand::thread_rng();
+        (0..nqbits)
+            .map(|_| ['I', 'X', 'Y', 'Z'][rng.gen_range(0..4)])
+            .collect()
+    }
+
+    #[test]
+    fn test_clifford_conventions() {
+        // U P |psi> = (U P U^dag) U |psi>, the conjugated operator being computed with PauliSet
+        let nqbits = 4;
+        for _ in 0..20 {
+            let circuit = CliffordCircuit::random(nqbits, 20);
+            let pauli = random_pauli(nqbits);
+            let mut pauli_set = PauliSet::new(nqbits);
+            pauli_set.insert(&pauli, false);
+            pauli_set.conjugate_with_circuit(&circuit);
+            let (phase, image) = pauli_set.get(0);
+
+            let psi = random_state(nqbits);
+            let mut left = psi.clone();
+            left.apply_pauli(false, &pauli);
+            left.apply_clifford_circuit(&circuit);
+            let mut right = psi;
+            right.apply_clifford_circuit(&circuit);
+            right.apply_pauli(phase, &image);
+            for (a, b) in left.amplitudes.iter().zip(right.amplitudes.iter()) {
+                assert!((*a - *b).norm() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotations() {
+        let mut rng = rand::thread_rng();
+        let nqbits = 4;
+        for _ in 0..20 {
+            let mut circuit = Circuit::new(nqbits);
+            for _ in 0..20 {
+                let angle = Parameter::Concrete(rng.gen_range(-3.0..3.0));
+                let q = rng.gen_range(0..nqbits);
+                circuit.gates.push(match rng.gen_range(0..5) {
+                    0 => Gate::Rz(q, angle),
+                    1 => Gate::Rx(q, angle),
+                    2 => Gate::Ry(q, angle),
+                    3 => Gate::Clifford(CliffordGate::CNOT(q, (q + 1) % nqbits)),
+                    _ => Gate::PauliRotation(random_pauli(nqbits), angle),
+                });
+            }
+            assert!(circuits_equivalent(
+                &circuit,
+                &circuit.decompose_rotations()
+            ));
+            // The circuit applies the extracted rotations, then its Clifford skeleton
+            let (rotations, clifford) = circuit.to_rotations();
+            let skeleton = circuit.clifford_skeleton();
+            assert_eq!(clifford, Tableau::from_circuit(&skeleton));
+            let mut rewritten = Circuit::from_rotations(&rotations);
+            rewritten.nqbits = nqbits;
+            rewritten.extend_with_clifford(&skeleton);
+            assert!(circuits_equivalent(&circuit, &rewritten));
+        }
+        // RZ and RZ via a Pauli rotation only differ by their definition
+        let theta = Parameter::Concrete(0.7);
+        let mut rz = Circuit::new(1);
+        rz.gates.push(Gate::Rz(0, theta.clone()));
+        let mut rotation = Circuit::new(1);
+        rotation
+            .gates
+            .push(Gate::PauliRotation("Z".to_owned(), theta));
+        assert_eq!(circuit_unitary(&rz), circuit_unitary(&rotation));
+    }
+
+    #[test]
+    fn test_equivalence_up_to_phase() {
+        // S S = Z and Z = i RZ(pi): equal up to a global phase
+        let mut zz = Circuit::new(2);
+        zz.gates.push(Gate::Clifford(CliffordGate::S(1)));
+        zz.gates.push(Gate::Clifford(CliffordGate::S(1)));
+        let mut rz = Circuit::new(2);
+        rz.gates
+            .push(Gate::Rz(1, Parameter::Concrete(std::f64::consts::PI)));
+        assert!(circuits_equivalent(&zz, &rz));
+        // A relative phase is detected
+        let mut s = Circuit::new(2);
+        s.gates.push(Gate::Clifford(CliffordGate::S(1)));
+        assert!(!circuits_equivalent(&zz, &s));
+        // Column-wise phases must agree: CZ and the identity only differ by phases on each column
+        let mut cz = Circuit::new(2);
+        cz.gates.push(Gate::Clifford(CliffordGate::CZ(0, 1)));
+        assert!(!circuits_equivalent(&cz, &Circuit::new(2)));
+        let mut state = StateVector::new(2);
+        state.apply_circuit(&cz);
+        assert!(state.equals_up_to_phase(&StateVector::new(2)));
+    }
+}
@@ -0,0 +1,462 @@
+use super::{CliffordCircuit, CliffordGate, Parameter, PauliLike, Tableau};
+
+/// A gate of a [Circuit]: a Clifford gate or a (possibly parametrized) Pauli rotation.
+///
+/// All rotations follow the convention R_P(theta) = exp(-i theta P / 2).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gate {
+    Clifford(CliffordGate),
+    /// RZ(theta) acting on a qubit
+    Rz(usize, Parameter),
+    /// RX(theta) acting on a qubit
+    Rx(usize, Parameter),
+    /// RY(theta) acting on a qubit
+    Ry(usize, Parameter),
+    /// Rotation around a Pauli operator given as a string over all the qubits (e.g. `"XIZ"`)
+    PauliRotation(String, Parameter),
+}
+
+impl From<CliffordGate> for Gate {
+    fn from(gate: CliffordGate) -> Self {
+        Self::Clifford(gate)
+    }
+}
+
+impl Gate {
+    /// Returns the qubits the gate acts on
+    pub fn qbits(&self) -> Vec<usize> {
+        match self {
+            Self::Clifford(gate) => gate.to_vec().1,
+            Self::Rz(i, _) | Self::Rx(i, _) | Self::Ry(i, _) => vec![*i],
+            Self::PauliRotation(axis, _) => support(axis),
+        }
+    }
+    /// Returns the inverse of the gate
+    pub fn dagger(&self) -> Self {
+        let flipped = |angle: &Parameter| {
+            let mut angle = angle.clone();
+            angle.flip_sign();
+            angle
+        };
+        match self {
+            Self::Clifford(gate) => Self::Clifford(gate.dagger()),
+            Self::Rz(i, angle) => Self::Rz(*i, flipped(angle)),
+            Self::Rx(i, angle) => Self::Rx(*i, flipped(angle)),
+            Self::Ry(i, angle) => Self::Ry(*i, flipped(angle)),
+            Self::PauliRotation(axis, angle) => Self::PauliRotation(axis.clone(), flipped(angle)),
+        }
+    }
+    /// Returns true if the gate is a rotation
+    pub fn is_rotation(&self) -> bool {
+        !matches!(self, Self::Clifford(_))
+    }
+    /// Returns the gate as a `(name, qubits)` pair, together with its angle (if any).
+    ///
+    /// Pauli rotations are named after the Pauli operators they apply on their support, e.g.
+    /// the rotation of axis `"XIZ"` becomes `("RXZ", [0, 2])`.
+    pub fn to_vec(&self) -> ((String, Vec<usize>), Option<Parameter>) {
+        match self {
+            Self::Clifford(gate) => (gate.to_vec(), None),
+            Self::Rz(i, angle) => (("RZ".to_owned(), vec![*i]), Some(angle.clone())),
+            Self::Rx(i, angle) => (("RX".to_owned(), vec![*i]), Some(angle.clone())),
+            Self::Ry(i, angle) => (("RY".to_owned(), vec![*i]), Some(angle.clone())),
+            Self::PauliRotation(axis, angle) => {
+                let name: String = "R"
+                    .chars()
+                    .chain(axis.chars().filter(|c| *c != 'I'))
+                    .collect();
+                ((name, support(axis)), Some(angle.clone()))
+            }
+        }
+    }
+}
+
+fn support(axis: &str) -> Vec<usize> {
+    axis.chars()
+        .enumerate()
+        .filter(|(_, c)| *c != 'I')
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Builds a Clifford circuit mapping the Pauli operator `axis` onto a Z operator acting on the
+/// returned qubit (up to a sign). `axis` should not be the identity.
+pub(crate) fn pauli_ladder(axis: &str) -> (CliffordCircuit, usize) {
+    let mut circuit = CliffordCircuit::new(axis.len());
+    let support = support(axis);
+    for (qbit, pauli) in axis.chars().enumerate() {
+        match pauli {
+            'X' => circuit.gates.push(CliffordGate::H(qbit)),
+            'Y' => circuit.gates.push(CliffordGate::SqrtX(qbit)),
+            _ => {}
+        }
+    }
+    let target = support[0];
+    for qbit in support.iter().skip(1) {
+        circuit.gates.push(CliffordGate::CNOT(*qbit, target));
+    }
+    (circuit, target)
+}
+
+/// A circuit interleaving Clifford gates and (possibly parametrized) Pauli rotations
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Circuit {
+    pub nqbits: usize,
+    pub gates: Vec<Gate>,
+}
+
+impl Circuit {
+    pub fn new(nqbits: usize) -> Self {
+        Self {
+            nqbits,
+            gates: Vec::new(),
+        }
+    }
+    /// Builds a circuit from a list of `(name, qubits)` pairs and the angles of its rotations
+    /// (in order of appearance).
+    ///
+    /// Supported names are the ones of [CliffordGate::from_vec], `X`, `Y`, `Z`, and the rotations
+    /// `RX`, `RY`, `RZ` and more generally `R` followed by one Pauli per qubit (e.g. `RXZ`).
+    pub fn from_vec(gates: &[(String, Vec<usize>)], angles: &[Parameter]) -> Result<Self, String> {
+        let nqbits = gates
+            .iter()
+            .flat_map(|(_, qbits)| qbits.iter())
+            .map(|qbit| qbit + 1)
+            .max()
+            .unwrap_or(0);
+        let mut circuit = Self::new(nqbits);
+        let mut angles = angles.iter();
+        for (name, qbits) in gates.iter() {
+            let arity = match name.as_str() {
+                "CX" | "CNOT" | "CZ" => 2,
+                _ if name.starts_with('R') => name.len() - 1,
+                _ => 1,
+            };
+            if qbits.len() != arity {
+                return Err(format!(
+                    "Gate {} expects {} qubits, got {}",
+                    name,
+                    arity,
+                    qbits.len()
+                ));
+            }
+            let q = *qbits
+                .first()
+                .ok_or(format!("Gate {} should act on at least one qubit", name))?;
+            match name.as_str() {
+                "H" | "S" | "Sd" | "SqrtX" | "SqrtXd" | "CX" | "CNOT" | "CZ" => circuit
+                    .gates
+                    .push(Gate::Clifford(CliffordGate::from_vec(name, qbits))),
+                "X" => circuit.gates.extend([
+                    Gate::Clifford(CliffordGate::SqrtX(q)),
+                    Gate::Clifford(CliffordGate::SqrtX(q)),
+                ]),
+                "Z" => circuit.gates.extend([
+                    Gate::Clifford(CliffordGate::S(q)),
+                    Gate::Clifford(CliffordGate::S(q)),
+                ]),
+                "Y" => circuit.gates.extend([
+                    Gate::Clifford(CliffordGate::SqrtX(q)),
+                    Gate::Clifford(CliffordGate::S(q)),
+                    Gate::Clifford(CliffordGate::S(q)),
+                    Gate::Clifford(CliffordGate::SqrtXd(q)),
+                ]),
+                _ if name.starts_with('R') && name[1..].chars().all(|c| "XYZ".contains(c)) => {
+                    let angle = angles
+                        .next()
+                        .ok_or(format!("Missing angle for gate {}", name))?
+                        .clone();
+                    let gate = match name.as_str() {
+                        "RX" => Gate::Rx(q, angle),
+                        "RY" => Gate::Ry(q, angle),
+                        "RZ" => Gate::Rz(q, angle),
+                        _ => {
+                            let mut axis = vec!['I'; nqbits];
+                            for (qbit, pauli) in qbits.iter().zip(name[1..].chars()) {
+                                axis[*qbit] = pauli;
+                            }
+                            Gate::PauliRotation(axis.into_iter().collect(), angle)
+                        }
+                    };
+                    circuit.gates.push(gate);
+                }
+                _ => return Err(format!("Unsupported gate {}", name)),
+            }
+        }
+        if angles.next().is_some() {
+            return Err("More angles than rotations".to_owned());
+        }
+        Ok(circuit)
+    }
+    /// Converts the circuit into a list of `(name, qubits)` pairs and the list of angles of its
+    /// rotations (see [Gate::to_vec])
+    pub fn to_vec(&self) -> (Vec<(String, Vec<usize>)>, Vec<Parameter>) {
+        let mut gates = Vec::new();
+        let mut angles = Vec::new();
+        for gate in self.gates.iter() {
+            let (pair, angle) = gate.to_vec();
+            gates.push(pair);
+            angles.extend(angle);
+        }
+        (gates, angles)
+    }
+    /// Builds the circuit applying a sequence of Pauli rotations
+    pub fn from_rotations(rotations: &[(String, Parameter)]) -> Self {
+        let nqbits = rotations.first().map_or(0, |(axis, _)| axis.len());
+        let mut circuit = Self::new(nqbits);
+        for (axis, angle) in rotations.iter() {
+            circuit
+                .gates
+                .push(Gate::PauliRotation(axis.clone(), angle.clone()));
+        }
+        circuit
+    }
+    /// Rewrites the circuit as a sequence of Pauli rotations followed by a final Clifford
+    /// operator. Identity rotations (i.e. global phases) are dropped.
+    pub fn to_rotations(&self) -> (Vec<(String, Parameter)>, Tableau) {
+        let mut clifford = Tableau::new(self.nqbits);
+        let mut rotations = Vec::new();
+        for gate in self.decompose_rotations().gates.iter() {
+            match gate {
+                Gate::Clifford(gate) => clifford.conjugate_with_gate(gate),
+                Gate::Rz(qbit, angle) => {
+                    let (phase, axis) = clifford.get_inverse_z(*qbit);
+                    let mut angle = angle.clone();
+                    if phase {
+                        angle.flip_sign();
+                    }
+                    rotations.push((axis, angle));
+                }
+                _ => unreachable!(),
+            }
+        }
+        (rotations, clifford)
+    }
+    /// Returns an equivalent circuit (up to a global phase) where all the rotations are RZ gates
+    pub fn decompose_rotations(&self) -> Self {
+        let mut output = Self::new(self.nqbits);
+        for gate in self.gates.iter() {
+            let (ladder, target, angle) = match gate {
+                Gate::Clifford(_) | Gate::Rz(_, _) => {
+                    output.gates.push(gate.clone());
+                    continue;
+                }
+                Gate::Rx(qbit, angle) => (
+                    CliffordCircuit {
+                        nqbits: self.nqbits,
+                        gates: vec![CliffordGate::H(*qbit)],
+                    },
+                    *qbit,
+                    angle,
+                ),
+                Gate::Ry(qbit, angle) => (
+                    CliffordCircuit {
+                        nqbits: self.nqbits,
+                        gates: vec![CliffordGate::SqrtX(*qbit)],
+                    },
+                    *qbit,
+                    angle,
+                ),
+                Gate::PauliRotation(axis, angle) => {
+                    if axis.chars().all(|c| c == 'I') {
+                        continue;
+                    }
+                    let (ladder, target) = pauli_ladder(axis);
+                    (ladder, target, angle)
+                }
+            };
+            output.extend_with_clifford(&ladder);
+            output.gates.push(Gate::Rz(target, angle.clone()));
+            output.extend_with_clifford(&ladder.dagger());
+        }
+        output
+    }
+    /// Appends all the gates of a Clifford circuit
+    pub fn extend_with_clifford(&mut self, other: &CliffordCircuit) {
+        self.gates
+            .extend(other.gates.iter().map(|gate| Gate::Clifford(*gate)));
+    }
+    /// Appends all the gates of another circuit
+    pub fn extend_with(&mut self, other: &Circuit) {
+        self.gates.extend_from_slice(&other.gates);
+    }
+    /// Counts the number of RZ gates
+    pub fn rz_count(&self) -> usize {
+        self.gates
+            .iter()
+            .filter(|gate| matches!(gate, Gate::Rz(_, _)))
+            .count()
+    }
+    /// Counts the number of rotations (of any kind)
+    pub fn rotation_count(&self) -> usize {
+        self.gates.iter().filter(|gate| gate.is_rotation()).count()
+    }
+    /// Counts the number of CNOT gates
+    pub fn cnot_count(&self) -> usize {
+        self.gates
+            .iter()
+            .filter(|gate| matches!(gate, Gate::Clifford(CliffordGate::CNOT(_, _))))
+            .count()
+    }
+    /// Counts the number of gates acting on at least two qubits
+    pub fn entangling_count(&self) -> usize {
+        self.gates
+            .iter()
+            .filter(|gate| gate.qbits().len() > 1)
+            .count()
+    }
+    fn depth_of(&self, filter: impl Fn(&Gate) -> bool) -> usize {
+        let mut depths: Vec<usize> = vec![0; self.nqbits];
+        for gate in self.gates.iter().filter(|gate| filter(gate)) {
+            let qbits = gate.qbits();
+            let gate_depth = qbits.iter().map(|q| depths[*q]).max().unwrap_or(0) + 1;
+            for q in qbits {
+                depths[q] = gate_depth;
+            }
+        }
+        depths.into_iter().max().unwrap_or(0)
+    }
+    /// Computes the depth of the circuit
+    pub fn depth(&self) -> usize {
+        self.depth_of(|_| true)
+    }
+    /// Computes the depth of the circuit, only counting gates acting on at least two qubits
+    pub fn entangling_depth(&self) -> usize {
+        self.depth_of(|gate| gate.qbits().len() > 1)
+    }
+    /// Computes the depth of the circuit, only counting rotations
+    pub fn rotation_depth(&self) -> usize {
+        self.depth_of(|gate| gate.is_rotation())
+    }
+    /// Returns the inverse of the circuit
+    pub fn dagger(&self) -> Self {
+        Self {
+            nqbits: self.nqbits,
+            gates: self.gates.iter().rev().map(|gate| gate.dagger()).collect(),
+        }
+    }
+    /// Returns the Clifford part of the circuit, i.e. the circuit without its rotations
+    pub fn clifford_skeleton(&self) -> CliffordCircuit {
+        let mut circuit = CliffordCircuit::new(self.nqbits);
+        for gate in self.gates.iter() {
+            if let Gate::Clifford(gate) = gate {
+                circuit.gates.push(*gate);
+            }
+        }
+        circuit
+    }
+}
+
+impl From<&CliffordCircuit> for Circuit {
+    fn from(circuit: &CliffordCircuit) -> Self {
+        let mut output = Self::new(circuit.nqbits);
+        output.extend_with_clifford(circuit);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn concrete(rotations: &[(String, Parameter)]) -> Vec<(String, f64)> {
+        rotations
+            .iter()
+            .map(|(axis, angle)| match angle {
+                Parameter::Concrete(x) => (axis.clone(), *x),
+                _ => panic!("Expected a concrete angle"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_vec_round_trip() {
+        let gates: Vec<(String, Vec<usize>)> = [
+            ("H", vec![0]),
+            ("CNOT", vec![0, 2]),
+            ("RZ", vec![1]),
+            ("RXZ", vec![2, 0]),
+            ("RY", vec![0]),
+        ]
+        .into_iter()
+        .map(|(name, qbits)| (name.to_owned(), qbits))
+        .collect();
+        let angles: Vec<Parameter> = (1..4).map(|i| Parameter::Concrete(i as f64)).collect();
+        let circuit = Circuit::from_vec(&gates, &angles).unwrap();
+        assert_eq!(circuit.nqbits, 3);
+        assert_eq!(
+            circuit.gates[3],
+            Gate::PauliRotation("ZIX".to_owned(), angles[1].clone())
+        );
+        assert_eq!(circuit.rotation_count(), 3);
+        assert_eq!(circuit.rz_count(), 1);
+        assert_eq!(circuit.entangling_count(), 2);
+        assert_eq!(circuit.depth(), 4);
+        assert_eq!(circuit.rotation_depth(), 2);
+        let (new_gates, new_angles) = circuit.to_vec();
+        assert_eq!(new_angles, angles);
+        assert_eq!(new_gates[3], ("RZX".to_owned(), vec![0, 2]));
+        assert_eq!(Circuit::from_vec(&new_gates, &new_angles).unwrap(), circuit);
+        assert!(Circuit::from_vec(&gates, &angles[..2]).is_err());
+        assert!(Circuit::from_vec(&[("RZ".to_owned(), vec![0, 1])], &angles[..1]).is_err());
+        assert!(Circuit::from_vec(&[("T".to_owned(), vec![0])], &[]).is_err());
+    }
+
+    #[test]
+    fn test_to_rotations() {
+        let rotations: Vec<(String, Parameter)> = ["XYZ", "IZZ", "YII", "IXI", "ZIZ"]
+            .iter()
+            .enumerate()
+            .map(|(i, axis)| (axis.to_string(), Parameter::Concrete(0.1 * (i + 1) as f64)))
+            .collect();
+        let mut circuit = Circuit::new(3);
+        circuit.gates.push(Gate::PauliRotation(
+            rotations[0].0.clone(),
+            rotations[0].1.clone(),
+        ));
+        circuit.gates.push(Gate::PauliRotation(
+            rotations[1].0.clone(),
+            rotations[1].1.clone(),
+        ));
+        circuit.gates.push(Gate::Ry(0, rotations[2].1.clone()));
+        circuit.gates.push(Gate::Rx(1, rotations[3].1.clone()));
+        circuit.gates.push(Gate::PauliRotation(
+            rotations[4].0.clone(),
+            rotations[4].1.clone(),
+        ));
+        let (extracted, clifford) = circuit.to_rotations();
+        assert_eq!(clifford, Tableau::new(3));
+        assert_eq!(concrete(&extracted), concrete(&rotations));
+        assert_eq!(
+            Circuit::from_rotations(&rotations).to_rotations().0,
+            extracted
+        );
+    }
+
+    #[test]
+    fn test_dagger() {
+        let circuit = Circuit::from_vec(
+            &[
+                ("S".to_owned(), vec![0]),
+                ("RZ".to_owned(), vec![0]),
+                ("CX".to_owned(), vec![0, 1]),
+                ("RXY".to_owned(), vec![0, 1]),
+            ],
+            &[Parameter::Concrete(0.3), Parameter::Concrete(0.5)],
+        )
+        .unwrap();
+        let mut full = circuit.clone();
+        full.extend_with(&circuit.dagger());
+        let (rotations, clifford) = full.to_rotations();
+        assert_eq!(clifford, Tableau::new(2));
+        // Each rotation is followed (up to commuting rotations) by its inverse
+        let rotations = concrete(&rotations);
+        assert_eq!(rotations.len(), 4);
+        assert_eq!(rotations[1].0, rotations[2].0);
+        assert_eq!(rotations[0].0, rotations[3].0);
+        assert!((rotations[1].1 + rotations[2].1).abs() < 1e-9);
+        assert!((rotations[0].1 + rotations[3].1).abs() < 1e-9);
+    }
+}
@@ -1,5 +1,8 @@
-use rand::Rng;
+use super::clifford_sampling::sample_clifford;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CliffordGate {
     CNOT(usize, usize),
     CZ(usize, usize),
@@ -52,6 +55,7 @@ impl CliffordGate {
     }
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CliffordCircuit {
     pub nqbits: usize,
     pub gates: Vec<CliffordGate>,
@@ -108,6 +112,13 @@ impl CliffordCircuit {
         circuit
     }
 
+    /// Samples a uniformly random Clifford operator from a seed and returns it in the
+    /// Bravyi-Maslov canonical form (see [sample_clifford])
+    pub fn random_uniform(nqubits: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        sample_clifford(nqubits, &mut rng)
+    }
+
     pub fn extend_with(&mut self, other: &CliffordCircuit) {
         self.gates.extend_from_slice(&other.gates);
     }
@@ -165,4 +176,24 @@ impl CliffordCircuit {
             gates: new_gates,
         }
     }
+    /// Returns a copy of the circuit where qubit `i` is replaced by qubit `mapping[i]`
+    pub fn relabel(&self, mapping: &[usize]) -> Self {
+        let new_gates = self
+            .gates
+            .iter()
+            .map(|gate| match gate {
+                CliffordGate::CNOT(i, j) => CliffordGate::CNOT(mapping[*i], mapping[*j]),
+                CliffordGate::CZ(i, j) => CliffordGate::CZ(mapping[*i], mapping[*j]),
+                CliffordGate::H(i) => CliffordGate::H(mapping[*i]),
+                CliffordGate::S(i) => CliffordGate::S(mapping[*i]),
+                CliffordGate::Sd(i) => CliffordGate::Sd(mapping[*i]),
+                CliffordGate::SqrtX(i) => CliffordGate::SqrtX(mapping[*i]),
+                CliffordGate::SqrtXd(i) => CliffordGate::SqrtXd(mapping[*i]),
+            })
+            .collect();
+        Self {
+            nqbits: self.nqbits,
+            gates: new_gates,
+        }
+    }
 }
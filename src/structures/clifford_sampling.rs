@@ -0,0 +1,277 @@
+//! Uniform sampling of Clifford operators using the Bravyi-Maslov canonical form
+//!
+//! Any Clifford operator can be written as F1 H P F2 where F1 and F2 are Hadamard-free Cliffords
+//! (CNOT, CZ, S and Pauli gates), H is a layer of Hadamard gates and P is a qubit permutation.
+//! Sampling (H, P) from the quantum Mallows distribution and F1, F2 uniformly yields a uniformly
+//! random Clifford operator (see arXiv:2003.09412).
+use super::{CliffordCircuit, CliffordGate};
+use crate::routines::f2_linalg::Matrix;
+use rand::Rng;
+
+/// Samples a layer of Hadamard gates and a permutation from the quantum Mallows distribution
+fn sample_quantum_mallows<R: Rng + ?Sized>(n: usize, rng: &mut R) -> (Vec<bool>, Vec<usize>) {
+    let mut hadamards = vec![false; n];
+    let mut permutation = vec![0; n];
+    let mut remaining: Vec<usize> = (0..n).collect();
+    for i in 0..n {
+        let m = n - i;
+        let eps = 4f64.powi(-(m as i32));
+        let r: f64 = rng.gen();
+        // P(index = j) is proportional to 2^-j for j in 0..2m
+        let index = (-(r + (1. - r) * eps).log2().ceil()).clamp(0., (2 * m - 1) as f64) as usize;
+        hadamards[i] = index < m;
+        let k = if index < m { index } else { 2 * m - index - 1 };
+        permutation[i] = remaining.remove(k);
+    }
+    (hadamards, permutation)
+}
+
+/// Samples a random unit lower triangular matrix
+fn sample_unit_lower_triangular<R: Rng + ?Sized>(n: usize, rng: &mut R) -> Matrix {
+    let mut matrix = vec![vec![false; n]; n];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        for entry in row.iter_mut().take(i) {
+            *entry = rng.gen_bool(0.5);
+        }
+        row[i] = true;
+    }
+    matrix
+}
+
+/// Samples a random symmetric matrix
+fn sample_symmetric<R: Rng + ?Sized>(n: usize, rng: &mut R) -> Matrix {
+    let lower: Matrix = (0..n)
+        .map(|i| (0..n).map(|j| j <= i && rng.gen_bool(0.5)).collect())
+        .collect();
+    (0..n)
+        .map(|i| (0..n).map(|j| lower[i.max(j)][i.min(j)]).collect())
+        .collect()
+}
+
+/// Appends the Hadamard-free Clifford described by a unit lower triangular matrix (CNOT layer)
+/// and a symmetric matrix (S and CZ layer)
+fn push_hadamard_free(circuit: &mut CliffordCircuit, delta: &Matrix, gamma: &Matrix) {
+    let n = circuit.nqbits;
+    for j in 0..n {
+        for (i, row) in delta.iter().enumerate().skip(j + 1) {
+            if row[j] {
+                circuit.gates.push(CliffordGate::CNOT(j, i));
+            }
+        }
+    }
+    for (i, row) in gamma.iter().enumerate() {
+        if row[i] {
+            circuit.gates.push(CliffordGate::S(i));
+        }
+        for (j, entry) in row.iter().enumerate().take(i) {
+            if *entry {
+                circuit.gates.push(CliffordGate::CZ(j, i));
+            }
+        }
+    }
+}
+
+/// Appends SWAP gates (as 3 CNOTs) moving qubit i to qubit `permutation[i]`
+fn push_permutation(circuit: &mut CliffordCircuit, permutation: &[usize]) {
+    let n = permutation.len();
+    // location[q] is the current position of the state initially on qubit q
+    let mut location: Vec<usize> = (0..n).collect();
+    let mut content: Vec<usize> = (0..n).collect();
+    for (qbit, target) in permutation.iter().enumerate() {
+        let (a, b) = (location[qbit], *target);
+        if a == b {
+            continue;
+        }
+        circuit.gates.push(CliffordGate::CNOT(a, b));
+        circuit.gates.push(CliffordGate::CNOT(b, a));
+        circuit.gates.push(CliffordGate::CNOT(a, b));
+        let other = content[b];
+        content.swap(a, b);
+        location[qbit] = b;
+        location[other] = a;
+    }
+}
+
+/// Builds the canonical form circuit: a Pauli layer, then F2, the permutation, the layer of
+/// Hadamard gates and F1 (in this order)
+fn canonical_form(
+    pauli: &[(bool, bool)],
+    f2: (&Matrix, &Matrix),
+    permutation: &[usize],
+    hadamards: &[bool],
+    f1: (&Matrix, &Matrix),
+) -> CliffordCircuit {
+    let n = pauli.len();
+    let mut circuit = CliffordCircuit::new(n);
+    for (qbit, (x, z)) in pauli.iter().enumerate() {
+        if *x {
+            circuit.gates.push(CliffordGate::SqrtX(qbit));
+            circuit.gates.push(CliffordGate::SqrtX(qbit));
+        }
+        if *z {
+            circuit.gates.push(CliffordGate::S(qbit));
+            circuit.gates.push(CliffordGate::S(qbit));
+        }
+    }
+    push_hadamard_free(&mut circuit, f2.0, f2.1);
+    push_permutation(&mut circuit, permutation);
+    for (qbit, h) in hadamards.iter().enumerate() {
+        if *h {
+            circuit.gates.push(CliffordGate::H(qbit));
+        }
+    }
+    push_hadamard_free(&mut circuit, f1.0, f1.1);
+    circuit
+}
+
+/// Samples a uniformly random n-qubit Clifford operator and returns it as a canonical form
+/// circuit. Phases are uniform too, i.e. the corresponding Tableau is uniformly random.
+pub fn sample_clifford<R: Rng + ?Sized>(n: usize, rng: &mut R) -> CliffordCircuit {
+    let (hadamards, permutation) = sample_quantum_mallows(n, rng);
+    let delta1 = sample_unit_lower_triangular(n, rng);
+    let gamma1 = sample_symmetric(n, rng);
+    let delta2 = sample_unit_lower_triangular(n, rng);
+    let gamma2 = sample_symmetric(n, rng);
+    let pauli: Vec<(bool, bool)> = (0..n)
+        .map(|_| (rng.gen_bool(0.5), rng.gen_bool(0.5)))
+        .collect();
+    canonical_form(
+        &pauli,
+        (&delta2, &gamma2),
+        &permutation,
+        &hadamards,
+        (&delta1, &gamma1),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::{IsometryTableau, Tableau};
+    use std::collections::HashMap;
+
+    /// Probability of sampling (hadamards, permutation) in `sample_quantum_mallows`
+    fn mallows_probability(hadamards: &[bool], permutation: &[usize]) -> f64 {
+        let n = permutation.len();
+        let mut remaining: Vec<usize> = (0..n).collect();
+        let mut probability = 1.;
+        for i in 0..n {
+            let m = n - i;
+            let k = remaining.iter().position(|q| *q == permutation[i]).unwrap();
+            remaining.remove(k);
+            let index = if hadamards[i] { k } else { 2 * m - 1 - k };
+            probability *= 0.5f64.powi(index as i32 + 1) / (1. - 0.25f64.powi(m as i32));
+        }
+        probability
+    }
+
+    fn all_matrices(n: usize, symmetric: bool) -> Vec<Matrix> {
+        let positions: Vec<(usize, usize)> = (0..n)
+            .flat_map(|i| (0..i + symmetric as usize).map(move |j| (i, j)))
+            .collect();
+        (0..1usize << positions.len())
+            .map(|bits| {
+                let mut matrix = vec![vec![false; n]; n];
+                if !symmetric {
+                    for (i, row) in matrix.iter_mut().enumerate() {
+                        row[i] = true;
+                    }
+                }
+                for (index, (i, j)) in positions.iter().enumerate() {
+                    matrix[*i][*j] = (bits >> index) & 1 == 1;
+                    matrix[*j][*i] |= symmetric && matrix[*i][*j];
+                }
+                matrix
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_canonical_form_distribution_is_uniform() {
+        // Exact distribution of the symplectic part for 2 qubits (720 elements)
+        let n = 2;
+        let deltas = all_matrices(n, false);
+        let gammas = all_matrices(n, true);
+        let pauli = vec![(false, false); n];
+        let mut distribution: HashMap<Vec<String>, f64> = HashMap::new();
+        for hadamards in [[false, false], [false, true], [true, false], [true, true]] {
+            for permutation in [[0, 1], [1, 0]] {
+                let probability = mallows_probability(&hadamards, &permutation)
+                    / (deltas.len() * gammas.len()).pow(2) as f64;
+                for delta1 in deltas.iter() {
+                    for gamma1 in gammas.iter() {
+                        for delta2 in deltas.iter() {
+                            for gamma2 in gammas.iter() {
+                                let circuit = canonical_form(
+                                    &pauli,
+                                    (delta2, gamma2),
+                                    &permutation,
+                                    &hadamards,
+                                    (delta1, gamma1),
+                                );
+                                let tableau = Tableau::from_circuit(&circuit);
+                                let key = (0..2 * n).map(|i| tableau.logicals.get(i).1).collect();
+                                *distribution.entry(key).or_insert(0.) += probability;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(distribution.len(), 720);
+        for probability in distribution.values() {
+            assert!((probability - 1. / 720.).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_push_permutation() {
+        let n = 5;
+        let mut circuit = CliffordCircuit::new(n);
+        push_permutation(&mut circuit, &[0, 1, 2, 3, 4]);
+        assert!(circuit.gates.is_empty());
+        let permutation = [3, 0, 4, 2, 1];
+        push_permutation(&mut circuit, &permutation);
+        let tableau = Tableau::from_circuit(&circuit);
+        for (i, target) in permutation.iter().enumerate() {
+            let mut expected = vec!['I'; n];
+            expected[*target] = 'X';
+            assert_eq!(
+                tableau.logicals.get(i).1,
+                expected.iter().collect::<String>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_seeded_sampling() {
+        for n in [0, 1, 4, 12] {
+            assert_eq!(
+                CliffordCircuit::random_uniform(n, 42).gates,
+                CliffordCircuit::random_uniform(n, 42).gates
+            );
+            let tableau = Tableau::random_uniform(n, 7);
+            assert_eq!(tableau, Tableau::random_uniform(n, 7));
+            assert_eq!(tableau.logicals.n, n);
+            let isometry = IsometryTableau::random_uniform(n, 2, 7);
+            assert_eq!((isometry.n, isometry.k), (n, 2));
+        }
+        assert_ne!(
+            Tableau::random_uniform(12, 1),
+            Tableau::random_uniform(12, 2)
+        );
+    }
+
+    #[test]
+    fn test_phases_are_uniform() {
+        let mut rng = rand::thread_rng();
+        let mut counts = [0; 4];
+        for _ in 0..4000 {
+            let tableau = Tableau::from_circuit(&sample_clifford(1, &mut rng));
+            let (x_phase, _) = tableau.logicals.get(0);
+            let (z_phase, _) = tableau.logicals.get(1);
+            counts[2 * x_phase as usize + z_phase as usize] += 1;
+        }
+        assert!(counts.iter().all(|count| (800..1200).contains(count)));
+    }
+}
@@ -32,9 +32,9 @@ impl GraphState {
         for row in adj.iter() {
             assert_eq!(row.len(), n, "Matrix is not square");
         }
-        for i in 0..n {
-            for j in 0..n {
-                assert_eq!(adj[i][j], adj[j][i], "Matrix is not symmetric");
+        for (i, row) in adj.iter().enumerate() {
+            for (j, entry) in row.iter().enumerate() {
+                assert_eq!(*entry, adj[j][i], "Matrix is not symmetric");
             }
         }
         Self { adj, n }
@@ -2,18 +2,19 @@ use petgraph::algo::{bellman_ford, min_spanning_tree};
 use petgraph::data::FromElements;
 use petgraph::prelude::*;
 use petgraph::Graph;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 fn convert_shortest_paths(
     all_paths: &[bellman_ford::Paths<NodeIndex, f64>],
     nodes: &[NodeIndex],
-) -> Vec<Vec<(usize, Vec<usize>)>> {
-    let mut my_paths: Vec<Vec<(usize, Vec<usize>)>> = Vec::new();
+) -> Vec<Vec<(f64, Vec<usize>)>> {
+    let mut my_paths: Vec<Vec<(f64, Vec<usize>)>> = Vec::new();
     for n1 in nodes.iter() {
         my_paths.push(Vec::new());
         for n2 in nodes.iter() {
             let path = get_shortest_path(all_paths, n1, n2);
-            my_paths.last_mut().unwrap().push((path.len() - 1, path));
+            let cost = all_paths[n1.index()].distances[n2.index()];
+            my_paths.last_mut().unwrap().push((cost, path));
         }
     }
     my_paths
@@ -36,7 +37,7 @@ fn get_shortest_path(
 }
 
 fn build_closure_mst(
-    all_paths: &[Vec<(usize, Vec<usize>)>],
+    all_paths: &[Vec<(f64, Vec<usize>)>],
     terminals: &[usize],
 ) -> Graph<(), f64, Undirected> {
     let mut g = Graph::new_undirected();
@@ -45,7 +46,7 @@ fn build_closure_mst(
     for (i1, n1) in nodes.iter().enumerate() {
         for (i2, n2) in nodes.iter().enumerate() {
             if i1 != i2 {
-                g.add_edge(*n1, *n2, all_paths[terminals[i1]][terminals[i2]].0 as f64);
+                g.add_edge(*n1, *n2, all_paths[terminals[i1]][terminals[i2]].0);
             }
         }
     }
@@ -91,6 +92,19 @@ impl SteinerTree {
     pub fn nodes(&self) -> Vec<usize> {
         self.mapping.keys().copied().collect()
     }
+    /// Returns the edges of the tree, labeled by qubit indices
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        self.graph
+            .raw_edges()
+            .iter()
+            .map(|edge| {
+                (
+                    self.inverse_mapping[&edge.source()],
+                    self.inverse_mapping[&edge.target()],
+                )
+            })
+            .collect()
+    }
     pub fn add_node(&mut self, node: usize) {
         let node_index = self.graph.add_node(());
         self.mapping.insert(node, node_index);
@@ -169,7 +183,27 @@ impl SteinerTree {
 fn build_steiner_from_mst(
     closure_mst: &Graph<(), f64, Undirected>,
     terminals: &[usize],
-    all_paths: &[Vec<(usize, Vec<usize>)>],
+    all_paths: &[Vec<(f64, Vec<usize>)>],
+    hardware: &UnGraph<(), f64, u32>,
+) -> SteinerTree {
+    let paths: Vec<&Vec<usize>> = closure_mst
+        .raw_edges()
+        .iter()
+        .map(|edge| {
+            let t1 = terminals[edge.source().index()];
+            let t2 = terminals[edge.target().index()];
+            &all_paths[t1][t2].1
+        })
+        .collect();
+    build_steiner_from_paths(&paths, terminals, hardware)
+}
+
+/// Builds a Steiner tree out of a list of paths connecting all the terminals, by computing a
+/// minimum spanning tree of their union and pruning its non-terminal leaves
+fn build_steiner_from_paths(
+    paths: &[&Vec<usize>],
+    terminals: &[usize],
+    hardware: &UnGraph<(), f64, u32>,
 ) -> SteinerTree {
     // Building the extended mst
     let mut g = Graph::new_undirected();
@@ -180,10 +214,7 @@ fn build_steiner_from_mst(
         nodes.insert(*i, nodei);
         inverse_map.insert(nodei, *i);
     }
-    for edge in closure_mst.raw_edges() {
-        let t1 = terminals[edge.source().index()];
-        let t2 = terminals[edge.target().index()];
-        let (_, path) = &all_paths[t1][t2];
+    for path in paths.iter() {
         for i in path.iter() {
             if !nodes.contains_key(i) {
                 let nodei = g.add_node(());
@@ -193,13 +224,16 @@ fn build_steiner_from_mst(
         }
         let mut left = path[0];
         for next_v in path.iter().skip(1) {
-            g.add_edge(nodes[&left], nodes[next_v], 1);
+            let coupling = hardware
+                .find_edge(NodeIndex::new(left), NodeIndex::new(*next_v))
+                .unwrap();
+            g.add_edge(nodes[&left], nodes[next_v], hardware[coupling]);
             left = *next_v;
         }
     }
-    let mst: Graph<(), i32, Undirected> = Graph::from_elements(min_spanning_tree(&g));
+    let mst: Graph<(), f64, Undirected> = Graph::from_elements(min_spanning_tree(&g));
     let mut as_st = SteinerTree {
-        graph: mst,
+        graph: mst.map(|_, _| (), |_, _| 1),
         mapping: nodes,
         inverse_mapping: inverse_map,
         terminals: terminals.to_owned(),
@@ -208,22 +242,247 @@ fn build_steiner_from_mst(
     as_st
 }
 
-fn get_steiner_tree(conv_paths: &[Vec<(usize, Vec<usize>)>], terminals: &[usize]) -> SteinerTree {
+fn get_steiner_tree(
+    conv_paths: &[Vec<(f64, Vec<usize>)>],
+    terminals: &[usize],
+    hardware: &UnGraph<(), f64, u32>,
+) -> SteinerTree {
     let closure = build_closure_mst(conv_paths, terminals);
-    build_steiner_from_mst(&closure, terminals, conv_paths)
+    build_steiner_from_mst(&closure, terminals, conv_paths, hardware)
+}
+
+/// Computes a minimum Steiner tree using the Dreyfus-Wagner dynamic programming algorithm.
+///
+/// The last terminal is used as the root. For each subset `S` of the other terminals and each
+/// node `v`, `cost[S][v]` is the minimum cost of a tree connecting `S` and `v`. Its complexity is
+/// `O(3^k n + 2^k n^2)` for `k` terminals and `n` nodes.
+fn get_exact_steiner_tree(
+    conv_paths: &[Vec<(f64, Vec<usize>)>],
+    terminals: &[usize],
+    hardware: &UnGraph<(), f64, u32>,
+) -> SteinerTree {
+    if terminals.len() <= 1 {
+        return build_steiner_from_paths(&[], terminals, hardware);
+    }
+    let n = conv_paths.len();
+    let k = terminals.len() - 1;
+    let root = terminals[k];
+    let nsubsets = 1 << k;
+    let mut cost = vec![vec![f64::INFINITY; n]; nsubsets];
+    // The node at which the tree of `S` connected to `v` branches
+    let mut branch = vec![vec![0; n]; nsubsets];
+    // For each subset `S` and branching node `u`, a subset `S'` of `S` such that the tree is the
+    // union of the trees of `S'` and `S \ S'` connected to `u`
+    let mut split = vec![vec![0; n]; nsubsets];
+    for (i, terminal) in terminals.iter().take(k).enumerate() {
+        for v in 0..n {
+            cost[1 << i][v] = conv_paths[*terminal][v].0;
+            branch[1 << i][v] = *terminal;
+        }
+    }
+    for subset in 1..nsubsets {
+        if subset.count_ones() < 2 {
+            continue;
+        }
+        let lowest = subset & subset.wrapping_neg();
+        let mut merged = vec![f64::INFINITY; n];
+        for u in 0..n {
+            // Enumerating the sub-subsets containing the lowest element of `subset`
+            let rest = subset ^ lowest;
+            let mut sub = rest;
+            loop {
+                let left = sub | lowest;
+                if left != subset {
+                    let value = cost[left][u] + cost[subset ^ left][u];
+                    if value < merged[u] {
+                        merged[u] = value;
+                        split[subset][u] = left;
+                    }
+                }
+                if sub == 0 {
+                    break;
+                }
+                sub = (sub - 1) & rest;
+            }
+        }
+        for v in 0..n {
+            for u in 0..n {
+                let value = merged[u] + conv_paths[u][v].0;
+                if value < cost[subset][v] {
+                    cost[subset][v] = value;
+                    branch[subset][v] = u;
+                }
+            }
+        }
+    }
+    // Collecting the paths of the optimal tree
+    let mut paths = Vec::new();
+    let mut stack = vec![(nsubsets - 1, root)];
+    while let Some((subset, v)) = stack.pop() {
+        let u = branch[subset][v];
+        paths.push(&conv_paths[u][v].1);
+        if subset.count_ones() >= 2 {
+            let left = split[subset][u];
+            stack.push((left, u));
+            stack.push((subset ^ left, u));
+        }
+    }
+    build_steiner_from_paths(&paths, terminals, hardware)
 }
+
+/// Terminal sets with less than this many terminals are handled by the exact Steiner tree solver
+pub const EXACT_STEINER_THRESHOLD: usize = 8;
+/// Maximal number of steps `3^k n + 2^k n^2` of the exact Steiner tree solver, for `k + 1`
+/// terminals on a hardware graph with `n` qubits. This covers the terminal sets below
+/// [EXACT_STEINER_THRESHOLD] on devices with up to ~360 qubits.
+pub const EXACT_STEINER_BUDGET: usize = 1 << 24;
 #[derive(Debug, Clone)]
 pub struct HardwareGraph {
     pub graph: UnGraph<(), f64, u32>,
-    shortest_paths: Vec<Vec<(usize, Vec<usize>)>>,
+    shortest_paths: Vec<Vec<(f64, Vec<usize>)>>,
+    /// Native CNOT orientations `(control, target)`. `None` if all couplings are bidirectional.
+    native: Option<HashSet<(usize, usize)>>,
 }
 
 impl HardwareGraph {
     pub fn from_couplings(couplings: &[(usize, usize)]) -> Self {
-        let mut graph = UnGraph::new_undirected();
         let n = couplings.iter().map(|c| c.0.max(c.1)).max().unwrap() + 1;
+        Self::build(n, couplings)
+    }
+
+    /// Builds a hardware graph from couplings `(i, j, cost)`, where `cost` is a non-negative
+    /// cost of applying a two-qubit gate on qubits `i` and `j` (e.g. an error rate or a duration).
+    ///
+    /// Shortest paths and Steiner trees minimize the total cost of the couplings they use.
+    pub fn from_weighted_couplings(couplings: &[(usize, usize, f64)]) -> Self {
+        let n = couplings.iter().map(|c| c.0.max(c.1)).max().unwrap() + 1;
+        Self::build_weighted(n, couplings)
+    }
+
+    /// Builds a hardware graph from directed couplings `(control, target)`: CNOT gates are only
+    /// natively supported in the given orientations.
+    ///
+    /// Connectivity is still undirected: a CNOT gate acting in the other orientation can be
+    /// implemented by conjugating it with Hadamard gates (see
+    /// [fix_cnot_orientation](crate::routines::orientation::fix_cnot_orientation)).
+    pub fn from_directed_couplings(couplings: &[(usize, usize)]) -> Self {
+        let n = couplings.iter().map(|c| c.0.max(c.1)).max().unwrap() + 1;
+        let mut undirected: Vec<(usize, usize)> = Vec::new();
+        for (i, j) in couplings.iter() {
+            if !undirected.contains(&(*i, *j)) && !undirected.contains(&(*j, *i)) {
+                undirected.push((*i, *j));
+            }
+        }
+        let mut hardware = Self::build(n, &undirected);
+        hardware.native = Some(couplings.iter().copied().collect());
+        hardware
+    }
+
+    /// Builds a line `0 - 1 - ... - (n-1)`
+    pub fn line(n: usize) -> Self {
+        let couplings: Vec<(usize, usize)> = (1..n).map(|i| (i - 1, i)).collect();
+        Self::build(n, &couplings)
+    }
+
+    /// Builds a ring `0 - 1 - ... - (n-1) - 0`
+    pub fn ring(n: usize) -> Self {
+        assert!(n >= 3, "A ring should have at least 3 qubits");
+        let couplings: Vec<(usize, usize)> = (0..n).map(|i| (i, (i + 1) % n)).collect();
+        Self::build(n, &couplings)
+    }
+
+    /// Builds a `rows` x `cols` 2D grid. Qubit `r * cols + c` sits on row `r` and column `c`.
+    pub fn grid(rows: usize, cols: usize) -> Self {
+        let mut couplings = Vec::new();
+        for r in 0..rows {
+            for c in 0..cols {
+                if c + 1 < cols {
+                    couplings.push((r * cols + c, r * cols + c + 1));
+                }
+                if r + 1 < rows {
+                    couplings.push((r * cols + c, (r + 1) * cols + c));
+                }
+            }
+        }
+        Self::build(rows * cols, &couplings)
+    }
+
+    /// Builds a hexagonal (honeycomb) lattice made of `rows` x `cols` hexagons.
+    ///
+    /// The lattice is built as a brick wall of `cols + 1` columns of `2 * rows + 2` qubits, whose
+    /// two dangling corner qubits are removed.
+    pub fn hexagonal(rows: usize, cols: usize) -> Self {
+        assert!(rows > 0 && cols > 0, "The lattice should contain a hexagon");
+        let height = 2 * rows + 2;
+        let index = |i: usize, j: usize| i * height + j;
+        let mut couplings = Vec::new();
+        for i in 0..=cols {
+            for j in 0..height - 1 {
+                couplings.push((index(i, j), index(i, j + 1)));
+            }
+        }
+        for i in 0..cols {
+            for j in (0..height).filter(|j| j % 2 == i % 2) {
+                couplings.push((index(i, j), index(i + 1, j)));
+            }
+        }
+        let corners = [index(0, height - 1), index(cols, (height - 1) * (cols % 2))];
+        Self::build((cols + 1) * height, &couplings).without_qubits(&corners)
+    }
+
+    /// Builds a heavy-hex lattice made of `rows` x `cols` hexagons.
+    ///
+    /// This is the hexagonal lattice of [HardwareGraph::hexagonal] where each coupling is replaced
+    /// by a path of length 2 going through an additional qubit. The qubits of the hexagonal
+    /// lattice come first, followed by one qubit per coupling.
+    pub fn heavy_hex(rows: usize, cols: usize) -> Self {
+        let hexagonal = Self::hexagonal(rows, cols);
+        let n = hexagonal.len();
+        let mut couplings = Vec::new();
+        for (index, edge) in hexagonal.graph.raw_edges().iter().enumerate() {
+            couplings.push((edge.source().index(), n + index));
+            couplings.push((n + index, edge.target().index()));
+        }
+        Self::build(n + hexagonal.graph.edge_count(), &couplings)
+    }
+
+    /// Builds a fully connected hardware graph
+    pub fn all_to_all(n: usize) -> Self {
+        let mut couplings = Vec::new();
+        for i in 0..n {
+            for j in i + 1..n {
+                couplings.push((i, j));
+            }
+        }
+        Self::build(n, &couplings)
+    }
+
+    /// Builds the subgraph obtained by removing some qubits.
+    /// The remaining qubits are relabeled in increasing order.
+    pub fn without_qubits(&self, removed: &[usize]) -> Self {
+        let kept: Vec<usize> = (0..self.len()).filter(|i| !removed.contains(i)).collect();
+        self.induced_subgraph(&kept)
+    }
+
+    fn build(n: usize, couplings: &[(usize, usize)]) -> Self {
+        let couplings: Vec<(usize, usize, f64)> =
+            couplings.iter().map(|c| (c.0, c.1, 1.)).collect();
+        Self::build_weighted(n, &couplings)
+    }
+
+    fn build_weighted(n: usize, couplings: &[(usize, usize, f64)]) -> Self {
+        for (i, j, cost) in couplings.iter() {
+            assert!(
+                *cost >= 0.,
+                "Coupling ({}, {}) has a negative cost {}",
+                i,
+                j,
+                cost
+            );
+        }
+        let mut graph = UnGraph::new_undirected();
         let nodes: Vec<_> = (0..n).map(|_| graph.add_node(())).collect();
-        graph.extend_with_edges(couplings.iter().map(|c| (nodes[c.0], nodes[c.1], 1.)));
+        graph.extend_with_edges(couplings.iter().map(|c| (nodes[c.0], nodes[c.1], c.2)));
         let all_paths: Vec<bellman_ford::Paths<NodeIndex, f64>> = nodes
             .iter()
             .map(|node_index| bellman_ford(&graph, *node_index).unwrap())
@@ -232,6 +491,7 @@ impl HardwareGraph {
         Self {
             graph,
             shortest_paths,
+            native: None,
         }
     }
     pub fn len(&self) -> usize {
@@ -242,7 +502,385 @@ impl HardwareGraph {
         self.graph.node_count() == 0
     }
 
+    /// Builds the subgraph induced by a list of qubits.
+    /// Qubit `i` of the subgraph corresponds to qubit `nodes[i]` of the original graph.
+    pub fn induced_subgraph(&self, nodes: &[usize]) -> Self {
+        let mut position = vec![None; self.len()];
+        for (i, node) in nodes.iter().enumerate() {
+            position[*node] = Some(i);
+        }
+        let couplings: Vec<(usize, usize, f64)> = self
+            .graph
+            .raw_edges()
+            .iter()
+            .filter_map(|edge| {
+                match (
+                    position[edge.source().index()],
+                    position[edge.target().index()],
+                ) {
+                    (Some(a), Some(b)) => Some((a, b, edge.weight)),
+                    _ => None,
+                }
+            })
+            .collect();
+        let mut subgraph = Self::build_weighted(nodes.len(), &couplings);
+        subgraph.native = self.native.as_ref().map(|native| {
+            native
+                .iter()
+                .filter_map(|(c, t)| match (position[*c], position[*t]) {
+                    (Some(a), Some(b)) => Some((a, b)),
+                    _ => None,
+                })
+                .collect()
+        });
+        subgraph
+    }
+
+    /// Checks if two qubits are coupled
+    pub fn are_connected(&self, i: usize, j: usize) -> bool {
+        self.graph
+            .find_edge(NodeIndex::new(i), NodeIndex::new(j))
+            .is_some()
+    }
+
+    /// Checks if some couplings only support CNOT gates in one orientation
+    pub fn is_directed(&self) -> bool {
+        self.native.is_some()
+    }
+
+    /// Checks if `CNOT(control, target)` is natively supported
+    pub fn is_native(&self, control: usize, target: usize) -> bool {
+        match &self.native {
+            Some(native) => native.contains(&(control, target)),
+            None => self.are_connected(control, target),
+        }
+    }
+
+    /// Returns the cost of the coupling between two qubits, if they are coupled
+    pub fn coupling_cost(&self, i: usize, j: usize) -> Option<f64> {
+        self.graph
+            .find_edge(NodeIndex::new(i), NodeIndex::new(j))
+            .map(|edge| self.graph[edge])
+    }
+
+    /// Returns the number of couplings on a shortest path between two qubits
+    pub fn distance(&self, i: usize, j: usize) -> usize {
+        self.shortest_paths[i][j].1.len() - 1
+    }
+
+    /// Returns the total cost of the couplings on a shortest path between two qubits
+    pub fn path_cost(&self, i: usize, j: usize) -> f64 {
+        self.shortest_paths[i][j].0
+    }
+
+    /// Returns the total cost of a list of couplings
+    pub fn edges_cost(&self, edges: &[(usize, usize)]) -> f64 {
+        edges
+            .iter()
+            .map(|(i, j)| {
+                self.coupling_cost(*i, *j)
+                    .unwrap_or_else(|| panic!("Qubits {} and {} are not coupled", i, j))
+            })
+            .sum()
+    }
+
+    /// Estimates the cost of the CNOT gates required to fold an operator along a Steiner tree.
+    ///
+    /// Each coupling of the tree is used once, and each non-terminal node requires an additional
+    /// CNOT, accounted for using its cheapest coupling in the tree. With unit costs, this is
+    /// equal to [SteinerTree::cnot_cost].
+    pub fn steiner_cost(&self, tree: &SteinerTree) -> f64 {
+        let edges = tree.edges();
+        let mut cost = self.edges_cost(&edges);
+        for node in tree.nodes() {
+            if !tree.terminals.contains(&node) {
+                cost += tree
+                    .neighbors(node)
+                    .iter()
+                    .map(|other| self.coupling_cost(node, *other).unwrap())
+                    .min_by(|a, b| a.total_cmp(b))
+                    .unwrap_or(0.);
+            }
+        }
+        cost
+    }
+
+    /// Returns a shortest path from qubit `i` to qubit `j` (both included)
+    pub fn shortest_path(&self, i: usize, j: usize) -> Vec<usize> {
+        let mut path = self.shortest_paths[i][j].1.clone();
+        path.reverse();
+        path
+    }
+
+    /// Computes a Steiner tree of a set of terminals.
+    ///
+    /// The tree is optimal if there are less than [EXACT_STEINER_THRESHOLD] terminals, and
+    /// computed using a 2-approximation otherwise. On very large devices, the exact solver is
+    /// also skipped when its cost exceeds [EXACT_STEINER_BUDGET].
     pub fn get_steiner_tree(&self, terminals: &[usize]) -> SteinerTree {
-        get_steiner_tree(&self.shortest_paths, terminals)
+        if self.uses_exact_steiner_tree(terminals.len()) {
+            self.get_exact_steiner_tree(terminals)
+        } else {
+            self.get_approximate_steiner_tree(terminals)
+        }
+    }
+
+    fn uses_exact_steiner_tree(&self, nterminals: usize) -> bool {
+        if nterminals >= EXACT_STEINER_THRESHOLD {
+            return false;
+        }
+        let n = self.len();
+        let k = nterminals.saturating_sub(1) as u32;
+        let cost = (3usize.pow(k) * n).checked_add((1usize << k).saturating_mul(n * n));
+        matches!(cost, Some(cost) if cost <= EXACT_STEINER_BUDGET)
+    }
+
+    /// Computes a Steiner tree using the metric closure MST 2-approximation
+    pub fn get_approximate_steiner_tree(&self, terminals: &[usize]) -> SteinerTree {
+        get_steiner_tree(&self.shortest_paths, terminals, &self.graph)
+    }
+
+    /// Computes a minimum Steiner tree using the Dreyfus-Wagner algorithm.
+    /// This is exponential in the number of terminals.
+    pub fn get_exact_steiner_tree(&self, terminals: &[usize]) -> SteinerTree {
+        get_exact_steiner_tree(&self.shortest_paths, terminals, &self.graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::algo::connected_components;
+    use rand::Rng;
+
+    fn degrees(hardware: &HardwareGraph) -> Vec<usize> {
+        (0..hardware.len())
+            .map(|i| hardware.graph.neighbors(NodeIndex::new(i)).count())
+            .collect()
+    }
+
+    #[test]
+    fn test_simple_topologies() {
+        let line = HardwareGraph::line(5);
+        assert_eq!(line.graph.edge_count(), 4);
+        assert_eq!(line.distance(0, 4), 4);
+        assert_eq!(line.shortest_path(0, 3), vec![0, 1, 2, 3]);
+        assert_eq!(HardwareGraph::line(1).len(), 1);
+
+        let ring = HardwareGraph::ring(6);
+        assert_eq!(ring.graph.edge_count(), 6);
+        assert_eq!(ring.distance(0, 5), 1);
+        assert_eq!(ring.distance(0, 3), 3);
+
+        let grid = HardwareGraph::grid(3, 4);
+        assert_eq!(grid.len(), 12);
+        assert_eq!(grid.graph.edge_count(), 17);
+        assert!(grid.are_connected(1, 5));
+        assert_eq!(grid.distance(0, 11), 5);
+
+        let full = HardwareGraph::all_to_all(5);
+        assert_eq!(full.graph.edge_count(), 10);
+        assert!(degrees(&full).iter().all(|d| *d == 4));
+    }
+
+    #[test]
+    fn test_hexagonal() {
+        let hexagon = HardwareGraph::hexagonal(1, 1);
+        assert_eq!(hexagon.len(), 6);
+        assert_eq!(hexagon.graph.edge_count(), 6);
+        assert!(degrees(&hexagon).iter().all(|d| *d == 2));
+        for (rows, cols) in [(2, 3), (3, 2), (4, 4)] {
+            let lattice = HardwareGraph::hexagonal(rows, cols);
+            assert_eq!(lattice.len(), 2 * (rows + 1) * (cols + 1) - 2);
+            let degrees = degrees(&lattice);
+            assert!(degrees.iter().all(|d| *d == 2 || *d == 3));
+            // Euler's formula for a planar graph with rows * cols hexagonal faces
+            assert_eq!(lattice.graph.edge_count(), lattice.len() + rows * cols - 1);
+            assert_eq!(connected_components(&lattice.graph), 1);
+        }
+    }
+
+    #[test]
+    fn test_heavy_hex() {
+        let hexagonal = HardwareGraph::hexagonal(2, 2);
+        let heavy_hex = HardwareGraph::heavy_hex(2, 2);
+        assert_eq!(
+            heavy_hex.len(),
+            hexagonal.len() + hexagonal.graph.edge_count()
+        );
+        assert_eq!(
+            heavy_hex.graph.edge_count(),
+            2 * hexagonal.graph.edge_count()
+        );
+        assert!(degrees(&heavy_hex).iter().all(|d| *d <= 3));
+        assert_eq!(heavy_hex.distance(0, 1), 2);
+        assert_eq!(connected_components(&heavy_hex.graph), 1);
+    }
+
+    #[test]
+    fn test_weighted_couplings() {
+        // A square 0 - 1 - 2 - 3 - 0 with an expensive coupling between 0 and 3
+        let hardware = HardwareGraph::from_weighted_couplings(&[
+            (0, 1, 1.),
+            (1, 2, 1.),
+            (2, 3, 1.),
+            (3, 0, 5.),
+        ]);
+        assert_eq!(hardware.coupling_cost(3, 0), Some(5.));
+        assert_eq!(hardware.coupling_cost(0, 2), None);
+        assert_eq!(hardware.shortest_path(0, 3), vec![0, 1, 2, 3]);
+        assert_eq!(hardware.distance(0, 3), 3);
+        assert_eq!(hardware.path_cost(0, 3), 3.);
+        let tree = hardware.get_steiner_tree(&[0, 3]);
+        assert_eq!(tree.len(), 4);
+        assert_eq!(hardware.steiner_cost(&tree), 5.);
+        // Costs are preserved in subgraphs
+        let subgraph = hardware.without_qubits(&[1]);
+        assert_eq!(subgraph.path_cost(0, 2), 5.);
+    }
+
+    #[test]
+    fn test_unit_steiner_cost() {
+        let grid = HardwareGraph::grid(3, 3);
+        for terminals in [vec![0, 8], vec![0, 2, 6], vec![1, 3, 5, 7], vec![4]] {
+            let tree = grid.get_steiner_tree(&terminals);
+            assert_eq!(grid.steiner_cost(&tree), tree.cnot_cost() as f64);
+        }
+    }
+
+    #[test]
+    fn test_directed_couplings() {
+        let hardware = HardwareGraph::from_directed_couplings(&[(0, 1), (2, 1), (2, 3), (3, 2)]);
+        assert!(hardware.is_directed());
+        assert_eq!(hardware.graph.edge_count(), 3);
+        assert!(hardware.is_native(0, 1));
+        assert!(!hardware.is_native(1, 0));
+        assert!(hardware.is_native(2, 1));
+        assert!(hardware.is_native(2, 3) && hardware.is_native(3, 2));
+        assert!(!hardware.is_native(0, 2));
+        let subgraph = hardware.without_qubits(&[0]);
+        assert!(subgraph.is_native(1, 0));
+        assert!(!subgraph.is_native(0, 1));
+        let undirected = HardwareGraph::line(3);
+        assert!(!undirected.is_directed());
+        assert!(undirected.is_native(1, 0) && undirected.is_native(0, 1));
+        assert!(!undirected.is_native(0, 2));
+    }
+
+    fn check_tree(hardware: &HardwareGraph, tree: &SteinerTree, terminals: &[usize]) {
+        assert!(terminals.iter().all(|t| tree.contains(*t)));
+        assert_eq!(tree.graph.edge_count() + 1, tree.len());
+        assert_eq!(connected_components(&tree.graph), 1);
+        for node in tree.nodes() {
+            assert!(terminals.contains(&node) || !tree.is_leaf(node));
+        }
+        for (i, j) in tree.edges() {
+            assert!(hardware.are_connected(i, j));
+        }
+    }
+
+    /// Minimum number of edges of a Steiner tree, by enumerating all connected sets of nodes
+    fn brute_force_steiner(hardware: &HardwareGraph, terminals: &[usize]) -> usize {
+        let n = hardware.len();
+        let mut best = usize::MAX;
+        for mask in 0..(1usize << n) {
+            if terminals.iter().any(|t| mask >> t & 1 == 0) {
+                continue;
+            }
+            let nodes: Vec<usize> = (0..n).filter(|i| mask >> i & 1 == 1).collect();
+            if connected_components(&hardware.induced_subgraph(&nodes).graph) == 1 {
+                best = best.min(nodes.len() - 1);
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_exact_steiner_tree() {
+        let grid = HardwareGraph::grid(3, 3);
+        let tree = grid.get_exact_steiner_tree(&[1, 3, 5, 7]);
+        check_tree(&grid, &tree, &[1, 3, 5, 7]);
+        assert_eq!(tree.len(), 5);
+        assert!(tree.contains(4));
+        let tree = grid.get_exact_steiner_tree(&[2]);
+        assert_eq!(tree.len(), 1);
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let size = rng.gen_range(2..6);
+            let terminals = rand::seq::index::sample(&mut rng, 9, size).into_vec();
+            let tree = grid.get_exact_steiner_tree(&terminals);
+            check_tree(&grid, &tree, &terminals);
+            assert_eq!(tree.len() - 1, brute_force_steiner(&grid, &terminals));
+        }
+    }
+
+    #[test]
+    fn test_exact_vs_approximate_steiner_tree() {
+        let mut rng = rand::thread_rng();
+        let weighted_couplings: Vec<(usize, usize, f64)> = HardwareGraph::grid(4, 4)
+            .graph
+            .raw_edges()
+            .iter()
+            .map(|e| {
+                (
+                    e.source().index(),
+                    e.target().index(),
+                    rng.gen_range(1..5) as f64,
+                )
+            })
+            .collect();
+        for hardware in [
+            HardwareGraph::grid(4, 4),
+            HardwareGraph::heavy_hex(1, 2),
+            HardwareGraph::from_weighted_couplings(&weighted_couplings),
+        ] {
+            for _ in 0..20 {
+                let size = rng.gen_range(2..EXACT_STEINER_THRESHOLD);
+                let terminals = rand::seq::index::sample(&mut rng, hardware.len(), size).into_vec();
+                let exact = hardware.get_exact_steiner_tree(&terminals);
+                let approximate = hardware.get_approximate_steiner_tree(&terminals);
+                check_tree(&hardware, &exact, &terminals);
+                check_tree(&hardware, &approximate, &terminals);
+                let exact_cost = hardware.edges_cost(&exact.edges());
+                let approximate_cost = hardware.edges_cost(&approximate.edges());
+                assert!(exact_cost <= approximate_cost);
+                assert!(approximate_cost <= 2. * exact_cost);
+            }
+        }
+    }
+
+    #[test]
+    fn test_steiner_tree_solver_selection() {
+        let grid = HardwareGraph::grid(3, 3);
+        assert!(grid.uses_exact_steiner_tree(7));
+        assert!(!grid.uses_exact_steiner_tree(EXACT_STEINER_THRESHOLD));
+        let tree = grid.get_steiner_tree(&[1, 3, 5, 7]);
+        assert_eq!(tree.len(), 5);
+        // Small terminal sets get the exact solver on large devices too
+        let mut rng = rand::thread_rng();
+        let hardware = HardwareGraph::heavy_hex(6, 6);
+        assert!(hardware.len() > 100);
+        assert!(hardware.uses_exact_steiner_tree(EXACT_STEINER_THRESHOLD - 1));
+        for size in 2..EXACT_STEINER_THRESHOLD {
+            let terminals = rand::seq::index::sample(&mut rng, hardware.len(), size).into_vec();
+            let tree = hardware.get_steiner_tree(&terminals);
+            check_tree(&hardware, &tree, &terminals);
+            let cost = hardware.edges_cost(&tree.edges());
+            let exact = hardware.get_exact_steiner_tree(&terminals);
+            assert_eq!(cost, hardware.edges_cost(&exact.edges()));
+            let approximate = hardware.get_approximate_steiner_tree(&terminals);
+            assert!(cost <= hardware.edges_cost(&approximate.edges()));
+        }
+    }
+
+    #[test]
+    fn test_without_qubits() {
+        let grid = HardwareGraph::grid(3, 3);
+        let corner_less = grid.without_qubits(&[0, 8]);
+        assert_eq!(corner_less.len(), 7);
+        assert_eq!(corner_less.graph.edge_count(), 8);
+        // Former qubits 1 and 2 are now qubits 0 and 1
+        assert!(corner_less.are_connected(0, 1));
+        assert_eq!(connected_components(&corner_less.graph), 1);
     }
 }
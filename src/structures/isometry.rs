@@ -1,10 +1,41 @@
+use super::clifford_sampling::sample_clifford;
 use super::pauli_like::PauliLike;
 use super::pauli_set::PauliSet;
 use crate::routines::f2_linalg::{row_echelon, Matrix};
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::fmt;
 
-#[derive(Clone, Debug, PartialEq)]
+/// Multiplies the Pauli operator `operator` by the commuting operator `other`, both given as a phase
+/// and the X bits followed by the Z bits
+fn multiply_operators(operator: &mut (bool, Vec<bool>), other: &(bool, Vec<bool>)) {
+    let n = operator.1.len() / 2;
+    let (x1, z1) = other.1.split_at(n);
+    let (x2, z2) = operator.1.split_at(n);
+    // Power of i picked up when multiplying the single-qubit Paulis (Aaronson-Gottesman)
+    let i_power = x1.iter().zip(z1).zip(x2.iter().zip(z2)).fold(
+        2 * (operator.0 as i32 + other.0 as i32),
+        |acc, ((x1, z1), (x2, z2))| {
+            let (x2, z2) = (*x2 as i32, *z2 as i32);
+            acc + match (x1, z1) {
+                (false, false) => 0,
+                (true, true) => z2 - x2,
+                (true, false) => z2 * (2 * x2 - 1),
+                (false, true) => x2 * (1 - 2 * z2),
+            }
+        },
+    );
+    assert!(
+        i_power.rem_euclid(2) == 0,
+        "Only commuting operators can be multiplied"
+    );
+    operator.0 = i_power.rem_euclid(4) == 2;
+    for (a, b) in operator.1.iter_mut().zip(other.1.iter()) {
+        *a ^= b;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct IsometryTableau {
     pub n: usize,
     pub k: usize,
@@ -65,6 +96,55 @@ impl IsometryTableau {
         }
         iso
     }
+    /// Samples a uniformly random isometry from a seed, as the restriction of a uniformly random
+    /// Clifford operator over n + k qubits
+    pub fn random_uniform(n: usize, k: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut iso = Self::new(n, k);
+        iso.conjugate_with_circuit(&sample_clifford(n + k, &mut rng));
+        iso
+    }
+    /// Returns a canonical copy of the isometry: the stabilizers are put in reduced row echelon
+    /// form and the logical operators are reduced modulo the stabilizers, phases included.
+    ///
+    /// Two isometries that only differ by their choice of stabilizer generators, by stabilizers
+    /// multiplied into their logical operators or by their internal layout have the same
+    /// canonical form.
+    pub fn canonical(&self) -> Self {
+        let mut rows: Vec<(bool, Vec<bool>)> = (0..self.k)
+            .map(|i| self.stabilizers.get_as_vec_bool(i))
+            .chain((0..2 * self.n).map(|i| self.logicals.get_as_vec_bool(i)))
+            .collect();
+        let mut rank = 0;
+        for col in 0..2 * (self.n + self.k) {
+            let Some(pivot) = (rank..self.k).find(|i| rows[*i].1[col]) else {
+                continue;
+            };
+            rows.swap(pivot, rank);
+            let pivot_row = rows[rank].clone();
+            for (i, row) in rows.iter_mut().enumerate() {
+                if i != rank && row.1[col] {
+                    multiply_operators(row, &pivot_row);
+                }
+            }
+            rank += 1;
+        }
+        let mut stabilizers = PauliSet::new(self.n + self.k);
+        let mut logicals = PauliSet::new(self.n + self.k);
+        for (i, (phase, row)) in rows.iter().enumerate() {
+            if i < self.k {
+                stabilizers.insert_vec_bool(row, *phase);
+            } else {
+                logicals.insert_vec_bool(row, *phase);
+            }
+        }
+        Self {
+            n: self.n,
+            k: self.k,
+            logicals,
+            stabilizers,
+        }
+    }
     /// Put the full Tableau in column echelon form
     /// Warning: this method scratches the phases
     pub fn normalize_inplace(&mut self) {
@@ -1,6 +1,8 @@
 //! This module contains all the data structures
 
+pub mod circuit;
 pub mod clifford_circuit;
+pub mod clifford_sampling;
 pub mod graph_state;
 pub mod hardware;
 pub mod isometry;
@@ -10,9 +12,14 @@ pub mod pauli;
 pub mod pauli_dag;
 pub mod pauli_like;
 pub mod pauli_set;
+#[cfg(feature = "serde")]
+mod serialization;
 pub mod tableau;
+mod tableau_index;
 
+pub use circuit::{Circuit, Gate};
 pub use clifford_circuit::{CliffordCircuit, CliffordGate};
+pub use clifford_sampling::sample_clifford;
 pub use graph_state::GraphState;
 pub use hardware::HardwareGraph;
 pub use isometry::IsometryTableau;
@@ -22,4 +29,4 @@ pub use pauli::Pauli;
 pub use pauli_dag::PauliDag;
 pub use pauli_like::PauliLike;
 pub use pauli_set::PauliSet;
-pub use tableau::Tableau;
+pub use tableau::{LogicalPair, Tableau};
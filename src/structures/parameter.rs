@@ -31,13 +31,14 @@ impl Parameter {
             Self::Concrete(x) => *x *= -1.,
         }
     }
+    /// Writes a concrete angle as `rem + k * pi/2` with `rem` in [0, pi/2) and `k` in 0..4
     pub fn simplify(&self) -> (Self, i32) {
         match self {
             Self::Abstract(_) => (self.clone(), 0),
             Self::Concrete(x) => {
-                let y = x % (2. * std::f64::consts::PI);
+                let y = x.rem_euclid(2. * std::f64::consts::PI);
                 let k = y.div_euclid(std::f64::consts::PI / 2.);
-                let rem = y % (std::f64::consts::PI / 2.);
+                let rem = y - k * std::f64::consts::PI / 2.;
                 (Self::Concrete(rem), k as i32)
             }
         }
@@ -94,3 +95,26 @@ impl ops::AddAssign<Parameter> for Parameter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    #[test]
+    fn test_simplify() {
+        for x in [-7., -PI, -FRAC_PI_4, -0.1, 0., 0.3, FRAC_PI_2, 5., 13.] {
+            let (rem, k) = Parameter::Concrete(x).simplify();
+            let Parameter::Concrete(rem) = rem else {
+                panic!("Simplifying a concrete angle yields a concrete angle");
+            };
+            assert!((0. ..FRAC_PI_2).contains(&rem));
+            assert!((0..4).contains(&k));
+            let error = (rem + k as f64 * FRAC_PI_2 - x).rem_euclid(2. * PI);
+            assert!(error < 1e-9 || 2. * PI - error < 1e-9);
+        }
+        let (rem, k) = Parameter::Concrete(-FRAC_PI_4).simplify();
+        assert_eq!(k, 3);
+        assert!((rem + Parameter::Concrete(-FRAC_PI_4)).is_zero());
+    }
+}
@@ -1,5 +1,5 @@
 use super::{CliffordCircuit, Metric, PauliLike, PauliSet};
-use crate::synthesis::pauli_network::greedy_pauli_network::single_synthesis_step;
+use crate::synthesis::pauli_network::greedy_pauli_network::single_synthesis_step_oriented;
 use petgraph::prelude::*;
 
 pub type Dag = DiGraph<usize, ()>;
@@ -19,10 +19,10 @@ pub fn build_dag_from_pauli_set(pauli_set: &PauliSet) -> Dag {
     dag
 }
 
-/// Computes the list of operators that can be synthesized
+/// Computes the list of operators that can be synthesized, i.e. the nodes without predecessors
 pub fn get_front_layer(dag: &Dag) -> Vec<NodeIndex> {
     dag.node_indices()
-        .filter(|node| dag.neighbors(*node).collect::<Vec<_>>().is_empty())
+        .filter(|node| dag.neighbors_directed(*node, Incoming).next().is_none())
         .collect()
 }
 
@@ -113,13 +113,25 @@ impl PauliDag {
         metric: &Metric,
         skip_sort: bool,
         synthesized_circuit: &mut CliffordCircuit,
+    ) {
+        self.single_step_synthesis_oriented(metric, skip_sort, synthesized_circuit, &|_, _| true);
+    }
+
+    /// Performs a single synthesis step, preferring CNOT gates `CNOT(i, j)` such that
+    /// `native(i, j)` holds
+    pub fn single_step_synthesis_oriented(
+        &mut self,
+        metric: &Metric,
+        skip_sort: bool,
+        synthesized_circuit: &mut CliffordCircuit,
+        native: &dyn Fn(usize, usize) -> bool,
     ) {
         if !skip_sort {
             self.front_nodes
                 .sort_by_cached_key(|k| self.pauli_set.support_size(k.index()));
         }
         let order: Vec<usize> = self.front_nodes.iter().map(|k| k.index()).collect();
-        let circuit_piece = single_synthesis_step(&self.pauli_set, metric, &order);
+        let circuit_piece = single_synthesis_step_oriented(&self.pauli_set, metric, &order, native);
 
         // Updating the global set of operators
         self.pauli_set.conjugate_with_circuit(&circuit_piece);
@@ -129,3 +141,21 @@ impl PauliDag {
         self.update_front_nodes();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_front_layer() {
+        let pauli_set = PauliSet::from_slice(&["XI", "ZI", "IZ", "ZX"].map(String::from));
+        let dag = build_dag_from_pauli_set(&pauli_set);
+        let mut front: Vec<usize> = get_front_layer(&dag)
+            .into_iter()
+            .map(|node| dag[node])
+            .collect();
+        front.sort();
+        // ZI and ZX come after XI, with which they anti-commute
+        assert_eq!(front, vec![0, 2]);
+    }
+}
@@ -4,6 +4,7 @@ use crate::synthesis::pauli_network::chunks::CHUNK_CONJUGATION_SCORE;
 use itertools::izip;
 use std::cmp::max;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 const WIDTH: usize = 64;
 
@@ -17,7 +18,9 @@ fn get_offset(index: usize) -> usize {
 
 /// A set of Pauli operators (module global phase)
 /// Conjugation by Clifford gates are vectorized
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Equality and hashing only depend on the stored operators and phases, not on the internal layout.
+#[derive(Clone, Debug)]
 pub struct PauliSet {
     pub n: usize,
     nstrides: usize,
@@ -521,6 +524,35 @@ impl PauliSet {
             self.insert_vec_bool(&axis, phase);
         }
     }
+    /// Returns a copy of the set with a compact layout (no popped operators, no unused strides)
+    pub fn canonical(&self) -> Self {
+        let mut pset = Self::new(self.n);
+        for i in 0..self.noperators {
+            let (phase, axis) = self.get_as_vec_bool(i);
+            pset.insert_vec_bool(&axis, phase);
+        }
+        pset
+    }
+}
+
+impl PartialEq for PauliSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.n == other.n
+            && self.noperators == other.noperators
+            && (0..self.noperators).all(|i| self.get_as_vec_bool(i) == other.get_as_vec_bool(i))
+    }
+}
+
+impl Eq for PauliSet {}
+
+impl Hash for PauliSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.n.hash(state);
+        self.noperators.hash(state);
+        for i in 0..self.noperators {
+            self.get_as_vec_bool(i).hash(state);
+        }
+    }
 }
 
 impl fmt::Display for PauliSet {
@@ -738,6 +770,35 @@ mod pauli_set_tests {
         assert_eq!(pset.get(0), (false, "X".to_owned()));
     }
     #[test]
+    fn layout_independent_equality() {
+        use std::collections::hash_map::DefaultHasher;
+        let hash = |pset: &PauliSet| {
+            let mut hasher = DefaultHasher::new();
+            pset.hash(&mut hasher);
+            hasher.finish()
+        };
+        let mut popped = PauliSet::new(2);
+        for _ in 0..70 {
+            popped.insert("ZZ", true);
+        }
+        popped.insert("XY", false);
+        for _ in 0..70 {
+            popped.pop();
+        }
+        let mut pset = PauliSet::new(2);
+        pset.insert("XY", false);
+        assert_eq!(popped, pset);
+        assert_eq!(hash(&popped), hash(&pset));
+        let canonical = popped.canonical();
+        assert_eq!((canonical.start_offset, canonical.nstrides), (0, 1));
+        assert_eq!(canonical, pset);
+        pset.set_phase(0, true);
+        assert_ne!(popped, pset);
+        pset.set_phase(0, false);
+        pset.insert("II", false);
+        assert_ne!(popped, pset);
+    }
+    #[test]
     fn commute_test() {
         let mut pset = PauliSet::new(2);
         pset.insert("ZI", false);
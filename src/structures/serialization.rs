@@ -0,0 +1,315 @@
+//! Serde encodings of the data structures whose memory layout is not meant to be persisted.
+//!
+//! See the crate documentation for a description of the encodings.
+use super::{GraphState, IsometryTableau, Parameter, PauliSet, Tableau};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ParameterRepr {
+    Concrete(f64),
+    Abstract(String),
+}
+
+impl Serialize for Parameter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Concrete(x) => ParameterRepr::Concrete(*x),
+            Self::Abstract(x) => ParameterRepr::Abstract(x.clone()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Parameter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match ParameterRepr::deserialize(deserializer)? {
+            ParameterRepr::Concrete(x) => Self::Concrete(x),
+            ParameterRepr::Abstract(x) => Self::Abstract(x),
+        })
+    }
+}
+
+/* ---------------------------------- Pauli strings ---------------------------------- */
+
+#[derive(Serialize, Deserialize)]
+struct PauliSetRepr {
+    n: usize,
+    operators: Vec<String>,
+}
+
+impl Serialize for PauliSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let operators = (0..self.len())
+            .map(|i| {
+                let (phase, string) = self.get(i);
+                format!("{}{}", if phase { '-' } else { '+' }, string)
+            })
+            .collect();
+        PauliSetRepr {
+            n: self.n,
+            operators,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PauliSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = PauliSetRepr::deserialize(deserializer)?;
+        let mut pauli_set = PauliSet::new(repr.n);
+        for operator in repr.operators.iter() {
+            let (phase, string) = match operator.chars().next() {
+                Some('+') => (false, &operator[1..]),
+                Some('-') => (true, &operator[1..]),
+                _ => return Err(D::Error::custom(format!("Missing sign in `{}`", operator))),
+            };
+            if string.len() != repr.n || string.chars().any(|c| !"IXYZ".contains(c)) {
+                return Err(D::Error::custom(format!(
+                    "Invalid Pauli string `{}` over {} qubits",
+                    operator, repr.n
+                )));
+            }
+            pauli_set.insert(string, phase);
+        }
+        Ok(pauli_set)
+    }
+}
+
+/* ------------------------------ Bit-packed operators ------------------------------ */
+
+/// Number of 32-bit words used to store an operator over `n` qubits
+fn words_per_operator(n: usize) -> usize {
+    (2 * n + 1).div_ceil(32)
+}
+
+/// Packs the operators of a PauliSet: each operator is stored as its `2n` X then Z bits followed
+/// by its sign bit, little endian, padded to a whole number of 32-bit words
+fn pack(pauli_set: &PauliSet) -> Vec<u32> {
+    let nwords = words_per_operator(pauli_set.n);
+    let mut data = Vec::with_capacity(nwords * pauli_set.len());
+    for i in 0..pauli_set.len() {
+        let (phase, mut bits) = pauli_set.get_as_vec_bool(i);
+        bits.push(phase);
+        let mut words = vec![0u32; nwords];
+        for (k, bit) in bits.iter().enumerate() {
+            words[k / 32] |= (*bit as u32) << (k % 32);
+        }
+        data.extend(words);
+    }
+    data
+}
+
+fn unpack(n: usize, data: &[u32], noperators: usize) -> Result<PauliSet, String> {
+    let nwords = words_per_operator(n);
+    if data.len() != nwords * noperators {
+        return Err(format!(
+            "Expected {} words for {} operators over {} qubits, got {}",
+            nwords * noperators,
+            noperators,
+            n,
+            data.len()
+        ));
+    }
+    let mut pauli_set = PauliSet::new(n);
+    for words in data.chunks(nwords) {
+        let bits: Vec<bool> = (0..2 * n + 1)
+            .map(|k| (words[k / 32] >> (k % 32)) & 1 == 1)
+            .collect();
+        pauli_set.insert_vec_bool(&bits[..2 * n], bits[2 * n]);
+    }
+    Ok(pauli_set)
+}
+
+#[derive(Serialize, Deserialize)]
+struct TableauRepr {
+    n: usize,
+    logicals: Vec<u32>,
+}
+
+impl Serialize for Tableau {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TableauRepr {
+            n: self.logicals.n,
+            logicals: pack(&self.logicals),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Tableau {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = TableauRepr::deserialize(deserializer)?;
+        let logicals = unpack(repr.n, &repr.logicals, 2 * repr.n).map_err(D::Error::custom)?;
+        Ok(Tableau { logicals })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct IsometryTableauRepr {
+    n: usize,
+    k: usize,
+    logicals: Vec<u32>,
+    stabilizers: Vec<u32>,
+}
+
+impl Serialize for IsometryTableau {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        IsometryTableauRepr {
+            n: self.n,
+            k: self.k,
+            logicals: pack(&self.logicals),
+            stabilizers: pack(&self.stabilizers),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for IsometryTableau {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = IsometryTableauRepr::deserialize(deserializer)?;
+        let nqbits = repr.n + repr.k;
+        Ok(IsometryTableau {
+            n: repr.n,
+            k: repr.k,
+            logicals: unpack(nqbits, &repr.logicals, 2 * repr.n).map_err(D::Error::custom)?,
+            stabilizers: unpack(nqbits, &repr.stabilizers, repr.k).map_err(D::Error::custom)?,
+        })
+    }
+}
+
+/* ---------------------------------- Graph states ---------------------------------- */
+
+#[derive(Serialize, Deserialize)]
+struct GraphStateRepr {
+    n: usize,
+    adj: Vec<String>,
+}
+
+impl Serialize for GraphState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let adj = self
+            .adj
+            .iter()
+            .map(|row| row.iter().map(|b| if *b { '1' } else { '0' }).collect())
+            .collect();
+        GraphStateRepr { n: self.n, adj }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GraphState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = GraphStateRepr::deserialize(deserializer)?;
+        if repr.adj.len() != repr.n
+            || repr
+                .adj
+                .iter()
+                .any(|row| row.len() != repr.n || row.chars().any(|c| c != '0' && c != '1'))
+        {
+            return Err(D::Error::custom(format!(
+                "Expected {} rows of {} bits",
+                repr.n, repr.n
+            )));
+        }
+        let adj: Vec<Vec<bool>> = repr
+            .adj
+            .iter()
+            .map(|row| row.chars().map(|c| c == '1').collect())
+            .collect();
+        let symmetric = adj
+            .iter()
+            .enumerate()
+            .all(|(i, row)| row.iter().enumerate().all(|(j, entry)| *entry == adj[j][i]));
+        if !symmetric {
+            return Err(D::Error::custom("Adjacency matrix is not symmetric"));
+        }
+        Ok(GraphState::from_adj(adj))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::{Circuit, CliffordCircuit, CliffordGate, Gate, Metric, PauliLike};
+    use serde::de::DeserializeOwned;
+
+    fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+        serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_circuits() {
+        let mut circuit = CliffordCircuit::new(3);
+        circuit.gates.push(CliffordGate::CNOT(0, 2));
+        circuit.gates.push(CliffordGate::SqrtXd(1));
+        assert_eq!(
+            serde_json::to_string(&circuit).unwrap(),
+            r#"{"nqbits":3,"gates":[{"CNOT":[0,2]},{"SqrtXd":1}]}"#
+        );
+        assert_eq!(round_trip(&circuit).gates, circuit.gates);
+        let mut circuit = Circuit::new(2);
+        circuit.gates.push(Gate::Clifford(CliffordGate::H(0)));
+        circuit.gates.push(Gate::Rz(1, Parameter::Concrete(0.5)));
+        circuit.gates.push(Gate::PauliRotation(
+            "XY".to_owned(),
+            Parameter::Abstract("theta".to_owned()),
+        ));
+        assert_eq!(
+            serde_json::to_string(&circuit).unwrap(),
+            r#"{"nqbits":2,"gates":[{"Clifford":{"H":0}},{"Rz":[1,0.5]},{"PauliRotation":["XY","theta"]}]}"#
+        );
+        assert_eq!(round_trip(&circuit), circuit);
+        assert_eq!(serde_json::to_string(&Metric::DEPTH).unwrap(), r#""depth""#);
+        assert!(matches!(round_trip(&Metric::COUNT), Metric::COUNT));
+    }
+
+    #[test]
+    fn test_pauli_set() {
+        let mut pauli_set = PauliSet::from_slice(&["XIZ".to_owned(), "YYI".to_owned()]);
+        pauli_set.set_phase(1, true);
+        assert_eq!(
+            serde_json::to_string(&pauli_set).unwrap(),
+            r#"{"n":3,"operators":["+XIZ","-YYI"]}"#
+        );
+        assert_eq!(round_trip(&pauli_set), pauli_set);
+        assert!(serde_json::from_str::<PauliSet>(r#"{"n":2,"operators":["+XIZ"]}"#).is_err());
+        assert!(serde_json::from_str::<PauliSet>(r#"{"n":2,"operators":["XI"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_tableaux() {
+        for n in [0, 2, 5, 16, 40] {
+            let tableau = Tableau::random(n);
+            assert_eq!(round_trip(&tableau), tableau);
+            let isometry = IsometryTableau::random(n, n / 2);
+            assert_eq!(round_trip(&isometry), isometry);
+        }
+        // X -> Z, Z -> -X on a single qubit: each generator fits in a word (x, z, sign)
+        let mut tableau = Tableau::new(1);
+        tableau.h(0);
+        tableau.s(0);
+        tableau.s(0);
+        assert_eq!(
+            serde_json::to_string(&tableau).unwrap(),
+            r#"{"n":1,"logicals":[2,5]}"#
+        );
+        assert!(serde_json::from_str::<Tableau>(r#"{"n":1,"logicals":[2]}"#).is_err());
+    }
+
+    #[test]
+    fn test_graph_state() {
+        let graph_state = GraphState::random(6);
+        let parsed = round_trip(&graph_state);
+        assert_eq!(parsed.adj, graph_state.adj);
+        assert!(serde_json::from_str::<GraphState>(r#"{"n":2,"adj":["01","00"]}"#).is_err());
+        assert_eq!(
+            serde_json::to_string(&GraphState::from_adj(vec![
+                vec![false, true],
+                vec![true, true]
+            ]))
+            .unwrap(),
+            r#"{"n":2,"adj":["01","11"]}"#
+        );
+    }
+}
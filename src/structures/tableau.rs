@@ -1,7 +1,10 @@
+use super::clifford_sampling::sample_clifford;
 use super::pauli_like::PauliLike;
 use super::pauli_set::PauliSet;
 use super::{CliffordCircuit, IsometryTableau};
-use rand::Rng;
+use crate::routines::f2_linalg::{f2_rank, kernel_f2, solve_f2, Matrix};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 fn compute_phase_product_pauli(pset0: &PauliSet, vec: &[bool]) -> bool {
     let mut phase = false;
@@ -38,7 +41,35 @@ fn compute_phase_product_pauli(pset0: &PauliSet, vec: &[bool]) -> bool {
     (((ifact % 4) >> 1) != 0) ^ phase
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Images (phase, Pauli string) of a pair X_i, Z_i
+pub type LogicalPair = ((bool, String), (bool, String));
+
+/// Binary representation (X part, then Z part) of a Pauli string
+fn pauli_bits(string: &str) -> Vec<bool> {
+    let x = string.chars().map(|c| c == 'X' || c == 'Y');
+    let z = string.chars().map(|c| c == 'Z' || c == 'Y');
+    x.chain(z).collect()
+}
+
+fn pauli_string(bits: &[bool]) -> String {
+    let n = bits.len() / 2;
+    (0..n)
+        .map(|i| match (bits[i], bits[i + n]) {
+            (false, false) => 'I',
+            (true, false) => 'X',
+            (false, true) => 'Z',
+            (true, true) => 'Y',
+        })
+        .collect()
+}
+
+/// Returns true if two Pauli operators given in binary representation anticommute
+fn symplectic_product(a: &[bool], b: &[bool]) -> bool {
+    let n = a.len() / 2;
+    (0..n).fold(false, |acc, i| acc ^ (a[i] & b[i + n]) ^ (a[i + n] & b[i]))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Tableau {
     pub logicals: PauliSet,
 }
@@ -62,6 +93,8 @@ impl Tableau {
         tab
     }
     /// Generates a random Tableau (no garantuees, just here for testing)
+    ///
+    /// See [Tableau::random_uniform] for uniform sampling.
     pub fn random(n: usize) -> Self {
         let mut rng = rand::thread_rng();
         let mut iso = Self::new(n);
@@ -89,6 +122,11 @@ impl Tableau {
         }
         iso
     }
+    /// Samples a uniformly random Tableau (phases included) from a seed
+    pub fn random_uniform(n: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self::from_circuit(&sample_clifford(n, &mut rng))
+    }
     /// Build a Tableau from a PauliSet
     pub fn from_operators(logicals: &Vec<(bool, String)>) -> Self {
         if logicals.is_empty() {
@@ -101,6 +139,131 @@ impl Tableau {
         }
         Self { logicals: pset }
     }
+    /// Builds the Tableau of a Clifford operator mapping Z_i to `stabilizers[i]`, i.e. preparing
+    /// their stabilizer state from |0...0>. See [Tableau::complete].
+    pub fn from_stabilizers(stabilizers: &[(bool, String)]) -> Result<Self, String> {
+        Self::complete(&[], stabilizers)
+    }
+    /// Completes a partial set of operators into a Tableau.
+    ///
+    /// The `k` pairs of `logicals` are the images of (X_i, Z_i) for i < k and the stabilizers are
+    /// the images of Z_k, Z_{k+1}, ... If there are less than n - k stabilizers, additional
+    /// commuting stabilizers are picked. The images of the remaining X operators (the
+    /// destabilizers) are then computed so that only X_i and Z_i anticommute. Picked stabilizers
+    /// and destabilizers have a + sign.
+    ///
+    /// Returns an error if the operators are malformed, violate the commutation relations or are
+    /// not independent.
+    pub fn complete(
+        logicals: &[LogicalPair],
+        stabilizers: &[(bool, String)],
+    ) -> Result<Self, String> {
+        let k = logicals.len();
+        let mut named: Vec<(String, &(bool, String))> = Vec::new();
+        for (i, (x, _)) in logicals.iter().enumerate() {
+            named.push((format!("X_{}", i), x));
+        }
+        for (i, (_, z)) in logicals.iter().enumerate() {
+            named.push((format!("Z_{}", i), z));
+        }
+        for (i, stabilizer) in stabilizers.iter().enumerate() {
+            named.push((format!("S_{}", i), stabilizer));
+        }
+        let n = named
+            .first()
+            .ok_or("Cannot infer the number of qubits of an empty set of operators")?
+            .1
+             .1
+            .len();
+        for (name, (_, string)) in named.iter() {
+            if string.len() != n || string.chars().any(|c| !"IXYZ".contains(c)) {
+                return Err(format!(
+                    "{} = {} is not a Pauli string over {} qubits",
+                    name, string, n
+                ));
+            }
+        }
+        if k + stabilizers.len() > n {
+            return Err(format!(
+                "{} logical pairs and {} stabilizers do not fit on {} qubits",
+                k,
+                stabilizers.len(),
+                n
+            ));
+        }
+        let bits: Matrix = named.iter().map(|(_, (_, s))| pauli_bits(s)).collect();
+        for a in 0..bits.len() {
+            for b in a + 1..bits.len() {
+                // Only X_i and Z_i = named[i + k] anticommute
+                let anticommute = a < k && b == a + k;
+                if symplectic_product(&bits[a], &bits[b]) != anticommute {
+                    return Err(format!(
+                        "{} and {} should {}",
+                        named[a].0,
+                        named[b].0,
+                        if anticommute {
+                            "anticommute"
+                        } else {
+                            "commute"
+                        }
+                    ));
+                }
+            }
+        }
+        if f2_rank(&bits) != bits.len() {
+            return Err("The operators are not independent".to_owned());
+        }
+
+        // Rows r such that r . v is the symplectic product of an operator with v
+        let dual =
+            |v: &Vec<bool>| -> Vec<bool> { v[n..].iter().chain(v[..n].iter()).copied().collect() };
+        let mut constraints: Matrix = bits.iter().map(dual).collect();
+        let mut all_bits = bits.clone();
+        let mut stabilizers: Vec<(bool, Vec<bool>)> = stabilizers
+            .iter()
+            .map(|(phase, string)| (*phase, pauli_bits(string)))
+            .collect();
+        while stabilizers.len() < n - k {
+            // Operators commuting with all the current ones contain a new independent one
+            let candidate = kernel_f2(&constraints, 2 * n)
+                .into_iter()
+                .find(|v| {
+                    let mut extended = all_bits.clone();
+                    extended.push(v.clone());
+                    f2_rank(&extended) == extended.len()
+                })
+                .expect("The commutant of an isotropic subspace is larger than the subspace");
+            constraints.push(dual(&candidate));
+            all_bits.push(candidate.clone());
+            stabilizers.push((false, candidate));
+        }
+        let mut destabilizers: Vec<Vec<bool>> = Vec::new();
+        for j in 0..n - k {
+            let mut target = vec![false; constraints.len()];
+            target[2 * k + j] = true;
+            let mut destabilizer = solve_f2(&constraints, &target, 2 * n)
+                .expect("Independent constraints always have a solution");
+            // Multiplying by stabilizers makes the destabilizers commute
+            for (l, other) in destabilizers.iter().enumerate() {
+                if symplectic_product(&destabilizer, other) {
+                    for (a, b) in destabilizer.iter_mut().zip(stabilizers[l].1.iter()) {
+                        *a ^= b;
+                    }
+                }
+            }
+            destabilizers.push(destabilizer);
+        }
+
+        let mut operators: Vec<(bool, String)> = logicals.iter().map(|(x, _)| x.clone()).collect();
+        operators.extend(destabilizers.iter().map(|d| (false, pauli_string(d))));
+        operators.extend(logicals.iter().map(|(_, z)| z.clone()));
+        operators.extend(
+            stabilizers
+                .iter()
+                .map(|(phase, s)| (*phase, pauli_string(s))),
+        );
+        Ok(Self::from_operators(&operators))
+    }
     /// Returns the inverse Tableau
     pub fn adjoint(&self) -> Self {
         let mut new_logicals = PauliSet::new(self.logicals.n);
@@ -166,6 +329,12 @@ impl Tableau {
         let phase = compute_phase_product_pauli(&self.logicals, &as_vec_bool);
         (phase, string)
     }
+    /// Returns a copy of the Tableau with a compact internal layout, see [PauliSet::canonical]
+    pub fn canonical(&self) -> Self {
+        Self {
+            logicals: self.logicals.canonical(),
+        }
+    }
     /// Lifts the Taleau into an IsometryTableau (k = 0)
     pub fn to_isometry(self) -> IsometryTableau {
         IsometryTableau {
@@ -273,6 +442,18 @@ impl std::ops::Mul<Tableau> for Tableau {
 #[cfg(test)]
 mod tests {
     use super::Tableau;
+    use crate::simulation::StateVector;
+    use crate::structures::Metric;
+    use crate::synthesis::clifford::isometry::isometry_synthesis;
+
+    fn check_relations(tableau: &Tableau) {
+        let n = tableau.logicals.n;
+        for a in 0..2 * n {
+            for b in a + 1..2 * n {
+                assert_eq!(tableau.logicals.commute(a, b), b != a + n);
+            }
+        }
+    }
 
     #[test]
     fn test_mul_adjoint() {
@@ -282,4 +463,69 @@ mod tests {
         let t4 = Tableau::new(5);
         assert_eq!(t3, t4);
     }
+
+    #[test]
+    fn test_from_stabilizers() {
+        for n in [2, 5, 8] {
+            let reference = Tableau::random(n);
+            let stabilizers: Vec<(bool, String)> =
+                (0..n).map(|i| reference.logicals.get(n + i)).collect();
+            let tableau = Tableau::from_stabilizers(&stabilizers).unwrap();
+            check_relations(&tableau);
+            for (i, stabilizer) in stabilizers.iter().enumerate() {
+                assert_eq!(&tableau.logicals.get(n + i), stabilizer);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_stabilizers_prepares_state() {
+        let stabilizers: Vec<(bool, String)> = [(true, "XYY"), (false, "ZZI"), (true, "IZZ")]
+            .iter()
+            .map(|(phase, string)| (*phase, string.to_string()))
+            .collect();
+        let tableau = Tableau::from_stabilizers(&stabilizers).unwrap();
+        let circuit = isometry_synthesis(&tableau.to_isometry(), &Metric::COUNT, 1);
+        let mut state = StateVector::new(3);
+        state.apply_clifford_circuit(&circuit);
+        for (phase, string) in stabilizers.iter() {
+            let mut stabilized = state.clone();
+            stabilized.apply_pauli(*phase, string);
+            assert_eq!(stabilized.inner_product(&state).re.round(), 1.);
+        }
+    }
+
+    #[test]
+    fn test_complete() {
+        for (n, k, s) in [(4, 1, 3), (6, 2, 1), (5, 0, 2), (3, 3, 0)] {
+            let reference = Tableau::random(n);
+            let logicals: Vec<_> = (0..k)
+                .map(|i| (reference.logicals.get(i), reference.logicals.get(n + i)))
+                .collect();
+            let stabilizers: Vec<_> = (k..k + s).map(|i| reference.logicals.get(n + i)).collect();
+            let tableau = Tableau::complete(&logicals, &stabilizers).unwrap();
+            check_relations(&tableau);
+            for (i, (x, z)) in logicals.iter().enumerate() {
+                assert_eq!(&tableau.logicals.get(i), x);
+                assert_eq!(&tableau.logicals.get(n + i), z);
+            }
+            for (j, stabilizer) in stabilizers.iter().enumerate() {
+                assert_eq!(&tableau.logicals.get(n + k + j), stabilizer);
+            }
+        }
+    }
+
+    #[test]
+    fn test_complete_errors() {
+        let pauli = |string: &str| (false, string.to_owned());
+        assert!(Tableau::from_stabilizers(&[]).is_err());
+        assert!(Tableau::from_stabilizers(&[pauli("XI"), pauli("ZI")]).is_err());
+        assert!(Tableau::from_stabilizers(&[pauli("ZZ"), (true, "ZZ".to_owned())]).is_err());
+        assert!(Tableau::from_stabilizers(&[pauli("ZZ"), pauli("Z")]).is_err());
+        assert!(Tableau::from_stabilizers(&[pauli("ZA")]).is_err());
+        assert!(Tableau::from_stabilizers(&[pauli("Z"), pauli("Z")]).is_err());
+        assert!(Tableau::complete(&[(pauli("XI"), pauli("IZ"))], &[]).is_err());
+        assert!(Tableau::complete(&[(pauli("XI"), pauli("ZI"))], &[pauli("XX")]).is_err());
+        assert!(Tableau::complete(&[(pauli("XI"), pauli("ZI"))], &[pauli("IX")]).is_ok());
+    }
 }
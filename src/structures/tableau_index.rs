@@ -0,0 +1,300 @@
+//! Bijective indexing of the Clifford group following Koenig and Smolin (arXiv:1406.2170)
+//!
+//! Symplectic matrices are handled as lists of rows in the interleaved basis
+//! (x_0, z_0, x_1, z_1, ...), row 2i (resp. 2i + 1) being the image of X_i (resp. Z_i).
+use super::Tableau;
+use crate::routines::f2_linalg::Matrix;
+
+/// Symplectic inner product in the interleaved basis
+fn inner(v: &[bool], w: &[bool]) -> bool {
+    (0..v.len() / 2).fold(false, |acc, i| {
+        acc ^ (v[2 * i] & w[2 * i + 1]) ^ (w[2 * i] & v[2 * i + 1])
+    })
+}
+
+/// Applies the symplectic transvection Z_k to v
+fn transvection(k: &[bool], v: &mut [bool]) {
+    if inner(k, v) {
+        for (a, b) in v.iter_mut().zip(k.iter()) {
+            *a ^= b;
+        }
+    }
+}
+
+fn add(v: &[bool], w: &[bool]) -> Vec<bool> {
+    v.iter().zip(w.iter()).map(|(a, b)| a ^ b).collect()
+}
+
+/// Finds h1 and h2 such that y = Z_h2 Z_h1 x (x and y being non-zero)
+fn find_transvection(x: &[bool], y: &[bool]) -> (Vec<bool>, Vec<bool>) {
+    let size = x.len();
+    if x == y {
+        return (vec![false; size], vec![false; size]);
+    }
+    if inner(x, y) {
+        return (add(x, y), vec![false; size]);
+    }
+    let non_zero = |v: &[bool], i: usize| v[2 * i] || v[2 * i + 1];
+    let mut z = vec![false; size];
+    // Looking for a qubit on which both x and y are non trivial
+    if let Some(i) = (0..size / 2).find(|i| non_zero(x, *i) && non_zero(y, *i)) {
+        z[2 * i] = x[2 * i] ^ y[2 * i];
+        z[2 * i + 1] = x[2 * i + 1] ^ y[2 * i + 1];
+        if !z[2 * i] && !z[2 * i + 1] {
+            z[2 * i + 1] = true;
+            if x[2 * i] != x[2 * i + 1] {
+                z[2 * i] = true;
+            }
+        }
+        return (add(x, &z), add(y, &z));
+    }
+    // Otherwise, z has to be non trivial on one qubit of x and one qubit of y
+    if let Some(i) = (0..size / 2).find(|i| non_zero(x, *i) && !non_zero(y, *i)) {
+        if x[2 * i] == x[2 * i + 1] {
+            z[2 * i + 1] = true;
+        } else {
+            z[2 * i + 1] = x[2 * i];
+            z[2 * i] = x[2 * i + 1];
+        }
+    }
+    if let Some(i) = (0..size / 2).find(|i| !non_zero(x, *i) && non_zero(y, *i)) {
+        if y[2 * i] == y[2 * i + 1] {
+            z[2 * i + 1] = true;
+        } else {
+            z[2 * i + 1] = y[2 * i];
+            z[2 * i] = y[2 * i + 1];
+        }
+    }
+    (add(x, &z), add(y, &z))
+}
+
+fn to_bits(value: u128, size: usize) -> Vec<bool> {
+    (0..size).map(|j| (value >> j) & 1 == 1).collect()
+}
+
+fn from_bits(bits: &[bool]) -> u128 {
+    bits.iter()
+        .enumerate()
+        .fold(0, |acc, (j, bit)| acc | ((*bit as u128) << j))
+}
+
+/// Transvections mapping the symplectic pair (e_0, e_1) to the first two rows of a matrix.
+/// `bits` are the 2n - 1 free bits used to pick the image of e_1.
+fn pair_transvections(f1: &[bool], bits: &[bool]) -> [Vec<bool>; 4] {
+    let size = f1.len();
+    let mut e1 = vec![false; size];
+    e1[0] = true;
+    let (t0, t1) = find_transvection(&e1, f1);
+    let mut h0 = e1;
+    h0[2..].copy_from_slice(&bits[1..]);
+    transvection(&t0, &mut h0);
+    transvection(&t1, &mut h0);
+    let f1 = if bits[0] {
+        vec![false; size]
+    } else {
+        f1.to_vec()
+    };
+    [t0, t1, h0, f1]
+}
+
+/// The symplectic matrix of index `index` over n qubits
+fn symplectic(index: u128, n: usize) -> Matrix {
+    if n == 0 {
+        return Vec::new();
+    }
+    let size = 2 * n;
+    let s = (1u128 << size) - 1;
+    let f1 = to_bits(index % s + 1, size);
+    let index = index / s;
+    let bits = to_bits(index % (1 << (size - 1)), size - 1);
+    let transvections = pair_transvections(&f1, &bits);
+    let sub = symplectic(index >> (size - 1), n - 1);
+    let mut matrix = vec![vec![false; size]; size];
+    matrix[0][0] = true;
+    matrix[1][1] = true;
+    for (i, row) in sub.into_iter().enumerate() {
+        matrix[i + 2][2..].copy_from_slice(&row);
+    }
+    for row in matrix.iter_mut() {
+        for t in transvections.iter() {
+            transvection(t, row);
+        }
+    }
+    matrix
+}
+
+/// The index of a symplectic matrix, inverse of [symplectic]
+fn symplectic_index(matrix: &Matrix) -> u128 {
+    let size = matrix.len();
+    if size == 0 {
+        return 0;
+    }
+    let s = (1u128 << size) - 1;
+    let f1 = &matrix[0];
+    let mut e1 = vec![false; size];
+    e1[0] = true;
+    let (t0, t1) = find_transvection(&e1, f1);
+    // Undoing the transvections mapping e_0 to f1 on the image of e_1 reveals the free bits
+    let mut u = matrix[1].clone();
+    transvection(&t1, &mut u);
+    transvection(&t0, &mut u);
+    let mut bits = vec![u[0]];
+    bits.extend_from_slice(&u[2..]);
+    let transvections = pair_transvections(f1, &bits);
+    let sub: Matrix = matrix[2..]
+        .iter()
+        .map(|row| {
+            let mut row = row.clone();
+            for t in transvections.iter().rev() {
+                transvection(t, &mut row);
+            }
+            row[2..].to_vec()
+        })
+        .collect();
+    let index = from_bits(&bits) + (symplectic_index(&sub) << (size - 1));
+    index * s + from_bits(f1) - 1
+}
+
+impl Tableau {
+    /// Order of the symplectic group Sp(2n, F2), i.e. the number of n-qubit Clifford operators
+    /// modulo Pauli operators and global phases
+    ///
+    /// Panics if the order does not fit in a u128 (n > 7).
+    pub fn symplectic_group_order(n: usize) -> u128 {
+        (1..=n as u32)
+            .try_fold(1u128, |acc, j| {
+                acc.checked_mul(((1u128 << (2 * j)) - 1) << (2 * j - 1))
+            })
+            .unwrap_or_else(|| panic!("The order of Sp({}, F2) does not fit in a u128", 2 * n))
+    }
+    /// Number of n-qubit Tableaux, i.e. the order of the Clifford group modulo global phases
+    ///
+    /// Panics if the order does not fit in a u128 (n > 7).
+    pub fn group_order(n: usize) -> u128 {
+        Self::symplectic_group_order(n)
+            .checked_mul(1 << (2 * n))
+            .unwrap_or_else(|| {
+                panic!(
+                    "The order of the {}-qubit Clifford group does not fit in a u128",
+                    n
+                )
+            })
+    }
+    /// Index of the Tableau in 0..symplectic_group_order(n), ignoring its phases
+    pub fn symplectic_index(&self) -> u128 {
+        let n = self.logicals.n;
+        assert!(n <= 7, "Indices do not fit in a u128 above 7 qubits");
+        let matrix: Matrix = (0..n)
+            .flat_map(|i| [i, i + n])
+            .map(|i| {
+                let (_, bits) = self.logicals.get_as_vec_bool(i);
+                (0..n).flat_map(|q| [bits[q], bits[q + n]]).collect()
+            })
+            .collect();
+        symplectic_index(&matrix)
+    }
+    /// Builds the Tableau of index `index` in 0..symplectic_group_order(n), with + phases
+    ///
+    /// Panics if the index is out of range.
+    pub fn from_symplectic_index(n: usize, index: u128) -> Self {
+        assert!(
+            index < Self::symplectic_group_order(n),
+            "Index {} is out of range for {} qubits",
+            index,
+            n
+        );
+        let matrix = symplectic(index, n);
+        let string = |row: &Vec<bool>| -> String {
+            (0..n)
+                .map(|q| match (row[2 * q], row[2 * q + 1]) {
+                    (false, false) => 'I',
+                    (true, false) => 'X',
+                    (false, true) => 'Z',
+                    (true, true) => 'Y',
+                })
+                .collect()
+        };
+        let x_images = (0..n).map(|i| (false, string(&matrix[2 * i])));
+        let z_images = (0..n).map(|i| (false, string(&matrix[2 * i + 1])));
+        Self::from_operators(&x_images.chain(z_images).collect())
+    }
+    /// Index of the Tableau in 0..group_order(n), the phases being the 2n lowest bits
+    pub fn index(&self) -> u128 {
+        let n = self.logicals.n;
+        assert!(n <= 7, "Indices do not fit in a u128 above 7 qubits");
+        let phases = (0..2 * n).fold(0, |acc, i| {
+            acc | ((self.logicals.get_phase(i) as u128) << i)
+        });
+        (self.symplectic_index() << (2 * n)) | phases
+    }
+    /// Builds the Tableau of index `index` in 0..group_order(n), inverse of [Tableau::index]
+    ///
+    /// Panics if the index is out of range.
+    pub fn from_index(n: usize, index: u128) -> Self {
+        assert!(
+            index < Self::group_order(n),
+            "Index {} is out of range for {} qubits",
+            index,
+            n
+        );
+        let mut tableau = Self::from_symplectic_index(n, index >> (2 * n));
+        for i in 0..2 * n {
+            tableau.logicals.set_phase(i, (index >> i) & 1 == 1);
+        }
+        tableau
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_group_order() {
+        assert_eq!(Tableau::group_order(0), 1);
+        assert_eq!(Tableau::symplectic_group_order(1), 6);
+        assert_eq!(Tableau::group_order(1), 24);
+        assert_eq!(Tableau::symplectic_group_order(2), 720);
+        assert_eq!(Tableau::symplectic_group_order(3), 1451520);
+        assert_eq!(
+            Tableau::group_order(7),
+            457620995529680351512370381586432000
+        );
+    }
+
+    #[test]
+    fn test_exhaustive_bijection() {
+        for n in 1..=2 {
+            let order = Tableau::group_order(n);
+            let mut seen = HashSet::new();
+            for index in 0..order {
+                let tableau = Tableau::from_index(n, index);
+                for a in 0..2 * n {
+                    for b in a + 1..2 * n {
+                        assert_eq!(tableau.logicals.commute(a, b), b != a + n);
+                    }
+                }
+                assert_eq!(tableau.index(), index);
+                assert!(seen.insert(format!("{:?}", tableau.logicals)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_round_trip() {
+        for n in 3..=7 {
+            for seed in 0..5 {
+                let tableau = Tableau::random_uniform(n, seed);
+                let index = tableau.index();
+                assert!(index < Tableau::group_order(n));
+                assert_eq!(Tableau::from_index(n, index), tableau);
+            }
+            let last = Tableau::symplectic_group_order(n) - 1;
+            assert_eq!(
+                Tableau::from_symplectic_index(n, last).symplectic_index(),
+                last
+            );
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! End-to-end optimization of circuits made of Clifford gates and Pauli rotations.
+//!
+//! The input circuit is first rewritten as a sequence of Pauli rotations followed by a final
+//! Clifford operator (see [Circuit::to_rotations]). The rotations are then merged using
+//! [zhang_rotation_optimization] and, optionally, simplified using the knowledge of the initial
+//! |0...0> state (see [full_initial_state_propagation]). Finally, the rotations are implemented
+//! using a Pauli network (see [greedy_pauli_network()]) and the remaining Clifford operator is
+//! synthesized using [isometry_synthesis].
+use crate::routines::rotation_optimization::{
+    full_initial_state_propagation, zhang_rotation_optimization,
+};
+use crate::structures::{Circuit, Metric, Parameter, PauliSet, Tableau};
+use crate::synthesis::clifford::isometry::isometry_synthesis;
+use crate::synthesis::pauli_network::{greedy_pauli_network, pauli_network_to_circuit};
+
+/// Optimizes a circuit made of Clifford gates and Pauli rotations.
+///
+/// The output circuit is made of Clifford gates and RZ gates and implements the same unitary
+/// operator, up to a global phase.
+///
+/// Arguments:
+/// * `metric` - the metric used for both the Pauli network and the final Clifford synthesis
+/// * `preserve_order` - if false, the Pauli network first tries to ignore the ordering of the
+///   rotations. This is only worthwhile when most of the rotations commute: if the resulting
+///   network cannot implement the rotations in a valid order, an order preserving network is
+///   used instead.
+/// * `initial_state_propagation` - if true, the rotations are simplified assuming that the
+///   circuit is applied to the |0...0> state. The output circuit is then only equivalent to the
+///   input circuit on this initial state.
+/// * `niter` - the number of random restarts of the Pauli network and Clifford synthesis
+pub fn optimize_circuit(
+    circuit: &Circuit,
+    metric: &Metric,
+    preserve_order: bool,
+    initial_state_propagation: bool,
+    niter: usize,
+) -> Circuit {
+    let nqubits = circuit.nqbits;
+    let (rotations, mut final_clifford) = circuit.to_rotations();
+    let (mut rotations, inverse_clifford) = zhang_rotation_optimization(rotations, nqubits);
+    final_clifford = final_clifford * inverse_clifford.adjoint();
+    if initial_state_propagation && !rotations.is_empty() {
+        let (new_rotations, clifford) = full_initial_state_propagation(&rotations);
+        rotations = new_rotations;
+        final_clifford = final_clifford * clifford;
+    }
+    rotations.retain(|(_, angle)| !angle.is_zero_mod_two_pi());
+
+    let mut output = Circuit::new(nqubits);
+    if !rotations.is_empty() {
+        let (axes, angles): (Vec<String>, Vec<Parameter>) = rotations.into_iter().unzip();
+        let bucket = PauliSet::from_slice(&axes);
+        let synthesize = |preserve_order: bool| {
+            let network =
+                greedy_pauli_network(&bucket, metric, preserve_order, niter, false, false);
+            pauli_network_to_circuit(&bucket, &angles, &network, true)
+                .map(|circuit| (network, circuit))
+        };
+        let (network, network_circuit) = synthesize(preserve_order)
+            .or_else(|_| synthesize(true))
+            .expect("Order preserving Pauli networks implement all the rotations");
+        output.extend_with(&network_circuit);
+        final_clifford = final_clifford * Tableau::from_circuit(&network).adjoint();
+    }
+    if final_clifford != Tableau::new(nqubits) {
+        let fix = isometry_synthesis(&final_clifford.to_isometry(), metric, niter);
+        output.extend_with_clifford(&fix);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::{circuits_equivalent, StateVector};
+    use crate::structures::Gate;
+    use rand::Rng;
+
+    fn random_circuit(n: usize, ngates: usize) -> Circuit {
+        let mut rng = rand::thread_rng();
+        let names = [
+            "CX", "CZ", "H", "S", "Sd", "SqrtX", "SqrtXd", "X", "Y", "Z", "RZ", "RX", "RY", "RXZ",
+        ];
+        let mut gates = Vec::new();
+        let mut angles = Vec::new();
+        for _ in 0..ngates {
+            let name = names[rng.gen_range(0..names.len())];
+            let q = rng.gen_range(0..n);
+            let qbits = if name == "CX" || name == "CZ" || name == "RXZ" {
+                vec![q, (q + rng.gen_range(1..n)) % n]
+            } else {
+                vec![q]
+            };
+            if name.starts_with('R') {
+                // Multiples of pi/4 exercise the merging of rotations into Clifford gates
+                let k = rng.gen_range(-8..8) as f64;
+                angles.push(Parameter::Concrete(k * std::f64::consts::FRAC_PI_4 + 0.1));
+            }
+            gates.push((name.to_owned(), qbits));
+        }
+        let mut circuit = Circuit::from_vec(&gates, &angles).unwrap();
+        circuit.nqbits = n;
+        circuit
+    }
+
+    #[test]
+    fn test_optimize_circuit() {
+        let n = 4;
+        for _ in 0..20 {
+            let circuit = random_circuit(n, 60);
+            for metric in [Metric::COUNT, Metric::DEPTH] {
+                for preserve_order in [true, false] {
+                    let output = optimize_circuit(&circuit, &metric, preserve_order, false, 1);
+                    assert!(output.rotation_count() <= circuit.rotation_count());
+                    assert_eq!(output.rotation_count(), output.rz_count());
+                    assert!(circuits_equivalent(&circuit, &output));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_optimize_circuit_initial_state_propagation() {
+        let n = 4;
+        for _ in 0..20 {
+            let circuit = random_circuit(n, 60);
+            for preserve_order in [true, false] {
+                let output = optimize_circuit(&circuit, &Metric::COUNT, preserve_order, true, 1);
+                let mut expected = StateVector::new(n);
+                expected.apply_circuit(&circuit);
+                let mut state = StateVector::new(n);
+                state.apply_circuit(&output);
+                assert!(state.equals_up_to_phase(&expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_optimize_circuit_merges_rotations() {
+        let gates = vec![
+            ("RZ".to_owned(), vec![0]),
+            ("CX".to_owned(), vec![0, 1]),
+            ("RZ".to_owned(), vec![0]),
+            ("CX".to_owned(), vec![0, 1]),
+        ];
+        let angles = vec![Parameter::Concrete(0.3), Parameter::Concrete(0.4)];
+        let circuit = Circuit::from_vec(&gates, &angles).unwrap();
+        let output = optimize_circuit(&circuit, &Metric::COUNT, true, false, 1);
+        assert_eq!(output.gates, vec![Gate::Rz(0, Parameter::Concrete(0.7))]);
+        assert!(circuits_equivalent(&circuit, &output));
+    }
+}
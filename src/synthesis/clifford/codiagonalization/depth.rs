@@ -54,21 +54,21 @@ fn pick_best_operation(
     let mut best_control = 0;
     let mut best_target = 0;
     let mut is_row = true;
-    for i in 0..scores_rows.len() {
-        for j in 0..scores_rows.len() {
-            if scores_rows[i][j] > best_score {
+    for (i, row) in scores_rows.iter().enumerate() {
+        for (j, score) in row.iter().enumerate() {
+            if *score > best_score {
                 best_control = i;
                 best_target = j;
-                best_score = scores_rows[i][j];
+                best_score = *score;
             }
         }
     }
-    for i in 0..scores_cols.len() {
-        for j in 0..scores_cols.len() {
-            if scores_cols[i][j] > best_score {
+    for (i, row) in scores_cols.iter().enumerate() {
+        for (j, score) in row.iter().enumerate() {
+            if *score > best_score {
                 best_control = i;
                 best_target = j;
-                best_score = scores_cols[i][j];
+                best_score = *score;
                 is_row = false;
             }
         }
@@ -0,0 +1,143 @@
+//! Codiagonalization of commuting Pauli operators under connectivity constraints.
+//!
+//! At each step, the operator that is not yet diagonal and has the cheapest Steiner tree is
+//! folded onto a single Z on the next qubit. All the other operators commute with it, so that they
+//! act as I or Z on this qubit, which is never touched again.
+//!
+//! Unlike [codiagonalize](super::codiagonalize), this does not reduce the X part of the operators
+//! before synthesizing a graph state (which could then be done with
+//! [synthesize_graph_state_on_hardware](crate::synthesis::clifford::graph_state::synthesize_graph_state_on_hardware)):
+//! the X part reduction uses CNOT gates between arbitrary qubits, and leaves the graph state on
+//! a set of qubits picked regardless of the hardware, which in general does not induce a
+//! connected subgraph.
+use crate::routines::tree_folding::{
+    best_elimination, cheapest_steiner_edges, fold_along_tree, with_terminal, FoldBasis,
+};
+use crate::structures::{CliffordCircuit, HardwareGraph, Metric, PauliLike, PauliSet};
+
+/// Returns true if operator `index` acts as X or Y on one of the qubits in `alive`
+fn has_x_part(pauli_set: &PauliSet, index: usize, alive: &[usize]) -> bool {
+    alive.iter().any(|q| pauli_set.get_entry(*q, index))
+}
+
+/// Codiagonalizes the operators, eliminating the qubits in the given order.
+fn reduce_on_hardware(
+    pauli_set: &PauliSet,
+    hardware: &HardwareGraph,
+    order: &[usize],
+) -> CliffordCircuit {
+    let mut work = pauli_set.clone();
+    let mut circuit = CliffordCircuit::new(pauli_set.n);
+    let mut alive: Vec<usize> = (0..pauli_set.n).collect();
+    for qbit in order.iter() {
+        let candidates = (0..work.len())
+            .filter(|i| has_x_part(&work, *i, &alive))
+            .map(|i| {
+                let support = work.get_support(i);
+                let terminals = support.into_iter().filter(|q| alive.contains(q)).collect();
+                (i, with_terminal(terminals, *qbit))
+            });
+        let Some((index, edges)) = cheapest_steiner_edges(hardware, &alive, candidates) else {
+            break;
+        };
+        let piece = fold_along_tree(&work, index, &edges, *qbit, FoldBasis::Z);
+        work.conjugate_with_circuit(&piece);
+        circuit.extend_with(&piece);
+        alive.retain(|q| q != qbit);
+    }
+    circuit
+}
+
+/// Codiagonalizes a set of pairwise commuting Pauli operators using only two-qubit gates acting
+/// on coupled qubits.
+///
+/// The qubits of the operators are identified with the qubits of the hardware graph, which is
+/// assumed to be connected. Up to `niter` elimination orders (one per BFS root) are tried and the
+/// best circuit according to `metric` is returned.
+pub fn codiagonalize_on_hardware(
+    pauli_set: &PauliSet,
+    hardware: &HardwareGraph,
+    metric: &Metric,
+    niter: usize,
+) -> CliffordCircuit {
+    assert_eq!(
+        pauli_set.n,
+        hardware.len(),
+        "The hardware graph and the operators should have the same number of qubits"
+    );
+    best_elimination(hardware, metric, niter, |order| {
+        reduce_on_hardware(pauli_set, hardware, order)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthesis::clifford::test_utils::check_connectivity;
+    use rand::Rng;
+
+    fn random_instance(n: usize, m: usize) -> PauliSet {
+        let mut rng = rand::thread_rng();
+        let mut pset = PauliSet::new(n);
+        for _ in 0..m {
+            let mut vec: Vec<bool> = vec![false; 2 * n];
+            for b in vec.iter_mut().take(n) {
+                *b = rng.gen::<bool>();
+            }
+            pset.insert_vec_bool(&vec, false);
+        }
+        for _ in 0..n * n {
+            let i = rng.gen::<usize>() % n;
+            loop {
+                let j = rng.gen::<usize>() % n;
+                if j != i {
+                    pset.cnot(i, j);
+                    let g2 = rng.gen::<bool>();
+                    if g2 {
+                        pset.h(j);
+                    } else {
+                        pset.s(j);
+                    }
+                    break;
+                }
+            }
+            let g1 = rng.gen::<bool>();
+            if g1 {
+                pset.h(i);
+            } else {
+                pset.s(i);
+            }
+        }
+        pset
+    }
+
+    fn check_codiagonalization(instance: &PauliSet, hardware: &HardwareGraph, metric: &Metric) {
+        let circuit = codiagonalize_on_hardware(instance, hardware, metric, 3);
+        check_connectivity(&circuit, hardware);
+        let mut copy_instance = instance.clone();
+        copy_instance.conjugate_with_circuit(&circuit);
+        for i in 0..instance.len() {
+            let (_, vec) = copy_instance.get_as_vec_bool(i);
+            assert!(vec[..instance.n].iter().all(|b| !*b));
+        }
+    }
+
+    #[test]
+    fn test_line() {
+        let hardware = HardwareGraph::line(7);
+        for metric in [Metric::COUNT, Metric::DEPTH] {
+            for _ in 0..10 {
+                check_codiagonalization(&random_instance(7, 3), &hardware, &metric);
+                check_codiagonalization(&random_instance(7, 20), &hardware, &metric);
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid() {
+        let hardware = HardwareGraph::grid(3, 3);
+        for _ in 0..10 {
+            check_codiagonalization(&random_instance(9, 20), &hardware, &Metric::COUNT);
+        }
+    }
+}
@@ -1,7 +1,9 @@
 mod common;
 pub mod count;
 pub mod depth;
+pub mod hardware;
 pub mod subset_wise;
 pub mod synthesis;
+pub use hardware::codiagonalize_on_hardware;
 pub use subset_wise::codiagonalize_subsetwise;
 pub use synthesis::codiagonalize;
@@ -13,16 +13,24 @@ enum Type {
 
 type Parities = (Vec<Vec<bool>>, Vec<(Type, usize, usize)>);
 
-fn gather_parities(circuit: &CliffordCircuit, n: usize) -> Parities {
+/// Gathers all the parities that can be added to the row of qubit `n` by inserting a single gate
+/// between qubit `n` and some qubit `qbit` such that `coupled(n, qbit)` holds.
+fn gather_parities(
+    circuit: &CliffordCircuit,
+    n: usize,
+    coupled: &dyn Fn(usize, usize) -> bool,
+) -> Parities {
     let mut graph_state = GraphState::new(n);
     let mut parities = Vec::new();
     let mut moves = Vec::new();
     let mut ei = vec![false; n];
     for i in 0..n {
-        ei[i] = true;
-        parities.push(ei.clone());
-        ei[i] = false;
-        moves.push((Type::Cz, 0, i));
+        if coupled(n, i) {
+            ei[i] = true;
+            parities.push(ei.clone());
+            ei[i] = false;
+            moves.push((Type::Cz, 0, i));
+        }
     }
     for (index, gate) in circuit.gates.iter().enumerate() {
         graph_state.conjugate_with_gate(gate);
@@ -31,36 +39,35 @@ fn gather_parities(circuit: &CliffordCircuit, n: usize) -> Parities {
                 for parity in parities.iter_mut() {
                     parity[*i] ^= parity[*j];
                 }
-                ei[*j] = true;
-                parities.push(ei.clone());
-                ei[*j] = false;
-                moves.push((Type::Cz, index + 1, *j));
-
-                parities.push(graph_state.adj[*i].clone());
-                moves.push((Type::Cnot, index + 1, *i));
+                if coupled(n, *j) {
+                    ei[*j] = true;
+                    parities.push(ei.clone());
+                    ei[*j] = false;
+                    moves.push((Type::Cz, index + 1, *j));
+                }
+                if coupled(n, *i) {
+                    parities.push(graph_state.adj[*i].clone());
+                    moves.push((Type::Cnot, index + 1, *i));
 
-                graph_state.s(*i);
-                parities.push(graph_state.adj[*i].clone());
-                graph_state.s(*i);
-                moves.push((Type::SCnotS, index + 1, *i));
+                    graph_state.s(*i);
+                    parities.push(graph_state.adj[*i].clone());
+                    graph_state.s(*i);
+                    moves.push((Type::SCnotS, index + 1, *i));
+                }
             }
 
             CliffordGate::CZ(i, j) => {
-                parities.push(graph_state.adj[*i].clone());
-                moves.push((Type::Cnot, index + 1, *i));
-
-                graph_state.s(*i);
-                parities.push(graph_state.adj[*i].clone());
-                graph_state.s(*i);
-                moves.push((Type::SCnotS, index + 1, *i));
+                for qbit in [*i, *j] {
+                    if coupled(n, qbit) {
+                        parities.push(graph_state.adj[qbit].clone());
+                        moves.push((Type::Cnot, index + 1, qbit));
 
-                parities.push(graph_state.adj[*j].clone());
-                moves.push((Type::Cnot, index + 1, *j));
-
-                graph_state.s(*j);
-                parities.push(graph_state.adj[*j].clone());
-                graph_state.s(*j);
-                moves.push((Type::SCnotS, index + 1, *j));
+                        graph_state.s(qbit);
+                        parities.push(graph_state.adj[qbit].clone());
+                        graph_state.s(qbit);
+                        moves.push((Type::SCnotS, index + 1, qbit));
+                    }
+                }
             }
             _ => {}
         }
@@ -70,14 +77,29 @@ fn gather_parities(circuit: &CliffordCircuit, n: usize) -> Parities {
 }
 
 pub fn synthesize_graph_state_count(graph: &GraphState, niter: usize) -> CliffordCircuit {
+    synthesize_graph_state_count_constrained(graph, niter, &|_, _| true)
+        .expect("Something went wrong during syndrome decoding :/")
+}
+
+/// Same as `synthesize_graph_state_count`, but only uses two-qubit gates acting on pairs of
+/// qubits `(i, j)` such that `coupled(i, j)` holds.
+/// Returns `None` if syndrome decoding fails to add some qubit to the graph.
+pub fn synthesize_graph_state_count_constrained(
+    graph: &GraphState,
+    niter: usize,
+    coupled: &dyn Fn(usize, usize) -> bool,
+) -> Option<CliffordCircuit> {
     let mut circuit = CliffordCircuit::new(graph.n);
     for i in 0..graph.n {
         if i > 0 {
-            let (parities, moves) = gather_parities(&circuit, i);
+            let (parities, moves) = gather_parities(&circuit, i, coupled);
             let mut target = vec![false; i];
             target[..i].copy_from_slice(&graph.adj[i][..i]);
-            let solution = information_set_decoding(&parities, &target, niter, true);
-            let solution = solution.expect("Something went wrong during syndrome decoding :/");
+            let solution = if parities.is_empty() {
+                target.iter().all(|b| !*b).then(Vec::new)
+            } else {
+                information_set_decoding(&parities, &target, niter, true)
+            }?;
             let mut new_circuit = CliffordCircuit::new(graph.n);
             let moves: Vec<(Type, usize, usize)> = solution
                 .iter()
@@ -133,5 +155,5 @@ pub fn synthesize_graph_state_count(graph: &GraphState, niter: usize) -> Cliffor
             circuit.gates.push(CliffordGate::S(i));
         }
     }
-    circuit
+    Some(circuit)
 }
@@ -4,12 +4,16 @@ use crate::structures::pauli_like::PauliLike;
 use petgraph::algo::maximum_matching;
 use petgraph::prelude::*;
 
-fn score_matrix(graph: &mut GraphState, qubits_used: &[bool]) -> Vec<Vec<i32>> {
+fn score_matrix(
+    graph: &mut GraphState,
+    qubits_used: &[bool],
+    coupled: &dyn Fn(usize, usize) -> bool,
+) -> Vec<Vec<i32>> {
     let base_value: i32 = graph.count_ones() as i32;
     let mut scores = vec![vec![-1; graph.n]; graph.n];
     for i in 0..graph.n {
         for j in i + 1..graph.n {
-            if !qubits_used[i] && !qubits_used[j] {
+            if !qubits_used[i] && !qubits_used[j] && coupled(i, j) {
                 graph.cnot(i, j);
                 scores[i][j] = base_value - graph.count_ones() as i32;
                 graph.cnot(i, j);
@@ -22,10 +26,10 @@ fn score_matrix(graph: &mut GraphState, qubits_used: &[bool]) -> Vec<Vec<i32>> {
 fn pick_best_operation(scores: &[Vec<i32>]) -> (i32, (usize, usize)) {
     let mut best_score = 0;
     let mut best_qubits: (usize, usize) = (0, 0);
-    for i in 0..scores.len() {
-        for j in 0..scores.len() {
-            if scores[i][j] > best_score {
-                best_score = scores[i][j];
+    for (i, row) in scores.iter().enumerate() {
+        for (j, score) in row.iter().enumerate() {
+            if *score > best_score {
+                best_score = *score;
                 best_qubits = (i, j);
             }
         }
@@ -34,13 +38,21 @@ fn pick_best_operation(scores: &[Vec<i32>]) -> (i32, (usize, usize)) {
 }
 
 pub fn get_czs(graph: &GraphState, qubits_used: &[bool]) -> CliffordCircuit {
+    get_czs_constrained(graph, qubits_used, &|_, _| true)
+}
+
+fn get_czs_constrained(
+    graph: &GraphState,
+    qubits_used: &[bool],
+    coupled: &dyn Fn(usize, usize) -> bool,
+) -> CliffordCircuit {
     let mut mgraph: UnGraph<(), i32> = UnGraph::new_undirected();
     for _ in 0..graph.n {
         mgraph.add_node(());
     }
     for i in 0..graph.n {
         for j in 0..graph.n {
-            if !qubits_used[i] && !qubits_used[j] && graph.adj[i][j] {
+            if !qubits_used[i] && !qubits_used[j] && graph.adj[i][j] && coupled(i, j) {
                 mgraph.add_edge(NodeIndex::new(i), NodeIndex::new(j), 1);
             }
         }
@@ -57,6 +69,19 @@ pub fn get_czs(graph: &GraphState, qubits_used: &[bool]) -> CliffordCircuit {
 
 pub fn synthesize_graph_state_depth(input_graph: &GraphState) -> CliffordCircuit {
     let mut graph = input_graph.clone();
+    let circuit = reduce_graph_state_depth(&mut graph, &|_, _| true);
+    circuit.dagger()
+}
+
+/// Greedily reduces `graph` to the empty graph by layers of CNOT and CZ gates acting on pairs of
+/// qubits `(i, j)` such that `coupled(i, j)` holds.
+///
+/// Stops early if no layer can make progress. Returns the reduction circuit and leaves the
+/// remaining graph in `graph`.
+pub fn reduce_graph_state_depth(
+    graph: &mut GraphState,
+    coupled: &dyn Fn(usize, usize) -> bool,
+) -> CliffordCircuit {
     let mut circuit = CliffordCircuit::new(graph.n);
     for i in 0..graph.n {
         if graph.adj[i][i] {
@@ -66,8 +91,9 @@ pub fn synthesize_graph_state_depth(input_graph: &GraphState) -> CliffordCircuit
     }
     while graph.count_ones() > 0 {
         let mut qubits_used: Vec<bool> = vec![false; graph.n];
+        let layer_start = circuit.gates.len();
         loop {
-            let scores = score_matrix(&mut graph, &qubits_used);
+            let scores = score_matrix(graph, &qubits_used, coupled);
             let (best_move, (control, target)) = pick_best_operation(&scores);
             if best_move == 0 {
                 break;
@@ -77,10 +103,12 @@ pub fn synthesize_graph_state_depth(input_graph: &GraphState) -> CliffordCircuit
             qubits_used[control] = true;
             qubits_used[target] = true;
         }
-        let cz_circuit = get_czs(&graph, &qubits_used);
+        let cz_circuit = get_czs_constrained(graph, &qubits_used, coupled);
         circuit.extend_with(&cz_circuit);
         graph.conjugate_with_circuit(&cz_circuit);
+        if circuit.gates.len() == layer_start {
+            break;
+        }
     }
-
-    circuit.dagger()
+    circuit
 }
@@ -0,0 +1,205 @@
+//! Synthesis of graph states under connectivity constraints.
+//!
+//! Two strategies are combined:
+//! * the count and depth heuristics, restricted to gates acting on coupled qubits. These may fail
+//!   to fully prepare the graph on sparse hardware graphs;
+//! * a tree elimination, that disconnects the qubits from the graph one by one. The neighborhood
+//!   of a qubit, seen as a Z operator, is folded onto the hardware neighbor of this qubit with the
+//!   cheapest Steiner tree, so that a single CZ gate removes all its edges.
+use super::count::synthesize_graph_state_count_constrained;
+use super::depth::reduce_graph_state_depth;
+use super::utils::extract_graph_state;
+use crate::routines::tree_folding::{
+    cheapest_steiner_edges, elimination_order, fold_along_tree, with_terminal, FoldBasis,
+};
+use crate::structures::{
+    CliffordCircuit, CliffordGate, GraphState, HardwareGraph, Metric, PauliLike, PauliSet,
+};
+
+/// Reduces `graph` to the empty graph, eliminating the qubits in the given order.
+/// Returns the reduction circuit.
+fn reduce_graph_state_on_tree(
+    graph: &mut GraphState,
+    hardware: &HardwareGraph,
+    order: &[usize],
+) -> CliffordCircuit {
+    let n = graph.n;
+    let mut circuit = CliffordCircuit::new(n);
+    let mut alive: Vec<usize> = (0..n).collect();
+    for qbit in order.iter() {
+        alive.retain(|q| q != qbit);
+        let support: Vec<usize> = alive
+            .iter()
+            .filter(|q| graph.adj[*qbit][**q])
+            .copied()
+            .collect();
+        if !support.is_empty() {
+            // The row of `qbit` behaves as a Z operator under the CNOT gates acting on the other
+            // qubits
+            let mut row = PauliSet::new(n);
+            let mut vec = vec![false; 2 * n];
+            for q in support.iter() {
+                vec[n + q] = true;
+            }
+            row.insert_vec_bool(&vec, false);
+            let candidates = alive
+                .iter()
+                .filter(|q| hardware.are_connected(*qbit, **q))
+                .map(|q| (*q, with_terminal(support.clone(), *q)));
+            let (neighbor, edges) = cheapest_steiner_edges(hardware, &alive, candidates)
+                .expect("The hardware graph should be connected");
+            let mut piece = fold_along_tree(&row, 0, &edges, neighbor, FoldBasis::Z);
+            piece.gates.push(CliffordGate::CZ(*qbit, neighbor));
+            graph.conjugate_with_circuit(&piece);
+            circuit.extend_with(&piece);
+        }
+        if graph.adj[*qbit][*qbit] {
+            graph.s(*qbit);
+            circuit.gates.push(CliffordGate::S(*qbit));
+        }
+    }
+    circuit
+}
+
+fn synthesize_graph_state_tree(graph: &GraphState, hardware: &HardwareGraph) -> CliffordCircuit {
+    let mut graph = graph.clone();
+    reduce_graph_state_on_tree(&mut graph, hardware, &elimination_order(hardware, 0)).dagger()
+}
+
+/// Restricted syndrome decoding: the qubits are added to the graph following a BFS order of the
+/// hardware graph, so that each new qubit is coupled to at least one previous qubit.
+fn synthesize_graph_state_count_hardware(
+    graph: &GraphState,
+    hardware: &HardwareGraph,
+    niter: usize,
+) -> Option<CliffordCircuit> {
+    let mut order = elimination_order(hardware, 0);
+    order.reverse();
+    let adj = order
+        .iter()
+        .map(|i| order.iter().map(|j| graph.adj[*i][*j]).collect())
+        .collect();
+    let relabeled = GraphState::from_adj(adj);
+    let coupled = |i: usize, j: usize| hardware.are_connected(order[i], order[j]);
+    let circuit = synthesize_graph_state_count_constrained(&relabeled, niter.max(1), &coupled)?;
+    Some(circuit.relabel(&order))
+}
+
+fn synthesize_graph_state_depth_hardware(
+    graph: &GraphState,
+    hardware: &HardwareGraph,
+) -> CliffordCircuit {
+    let mut graph = graph.clone();
+    let coupled = |i: usize, j: usize| hardware.are_connected(i, j);
+    let mut circuit = reduce_graph_state_depth(&mut graph, &coupled);
+    if graph.count_ones() > 0 {
+        let order = elimination_order(hardware, 0);
+        circuit.extend_with(&reduce_graph_state_on_tree(&mut graph, hardware, &order));
+    }
+    circuit.dagger()
+}
+
+/// Synthesizes a graph state using only two-qubit gates acting on coupled qubits.
+///
+/// The qubits of the graph are identified with the qubits of the hardware graph, which is assumed
+/// to be connected. The restricted count (resp. depth) heuristic is compared with a Steiner-tree
+/// based elimination and the best circuit according to `metric` is returned.
+pub fn synthesize_graph_state_on_hardware(
+    graph: &GraphState,
+    hardware: &HardwareGraph,
+    metric: &Metric,
+    niter: usize,
+) -> CliffordCircuit {
+    assert_eq!(
+        graph.n,
+        hardware.len(),
+        "The hardware graph and the graph state should have the same number of qubits"
+    );
+    let tree_circuit = synthesize_graph_state_tree(graph, hardware);
+    let circuit = match metric {
+        Metric::COUNT => synthesize_graph_state_count_hardware(graph, hardware, niter),
+        Metric::DEPTH => Some(synthesize_graph_state_depth_hardware(graph, hardware)),
+    };
+    match circuit {
+        Some(circuit) if metric.on_circuit(&circuit) <= metric.on_circuit(&tree_circuit) => circuit,
+        _ => tree_circuit,
+    }
+}
+
+/// Synthesizes a stabilizer state using only two-qubit gates acting on coupled qubits.
+///
+/// See [synthesize_graph_state_on_hardware] for the meaning of the parameters.
+pub fn synthesize_stabilizer_state_on_hardware(
+    stabilizers: &PauliSet,
+    hardware: &HardwareGraph,
+    metric: &Metric,
+    niter: usize,
+) -> CliffordCircuit {
+    let (graph, h_circuit) = extract_graph_state(stabilizers);
+    let circuit = synthesize_graph_state_on_hardware(&graph, hardware, metric, niter);
+    let mut output = CliffordCircuit::new(stabilizers.n);
+    for i in 0..stabilizers.n {
+        output.gates.push(CliffordGate::H(i));
+    }
+    output.extend_with(&circuit);
+    output.extend_with(&h_circuit);
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::IsometryTableau;
+    use crate::synthesis::clifford::test_utils::check_connectivity;
+
+    #[test]
+    fn test_tree_elimination() {
+        let hardware = HardwareGraph::line(6);
+        for _ in 0..10 {
+            let gs = GraphState::random(6);
+            let circuit = synthesize_graph_state_tree(&gs, &hardware);
+            check_connectivity(&circuit, &hardware);
+            let mut graph = GraphState::new(6);
+            graph.conjugate_with_circuit(&circuit);
+            assert_eq!(gs.adj, graph.adj);
+        }
+    }
+
+    #[test]
+    fn test_gs_on_hardware() {
+        let hardware = HardwareGraph::grid(3, 3);
+        for metric in [Metric::COUNT, Metric::DEPTH] {
+            for _ in 0..10 {
+                let gs = GraphState::random(9);
+                let circuit = synthesize_graph_state_on_hardware(&gs, &hardware, &metric, 100);
+                check_connectivity(&circuit, &hardware);
+                let mut graph = GraphState::new(9);
+                graph.conjugate_with_circuit(&circuit);
+                assert_eq!(gs.adj, graph.adj);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stab_state_on_hardware() {
+        let hardware = HardwareGraph::grid(3, 3);
+        for metric in [Metric::COUNT, Metric::DEPTH] {
+            for _ in 0..10 {
+                let mut iso = IsometryTableau::random(0, 9);
+                iso.normalize_inplace();
+                let circuit = synthesize_stabilizer_state_on_hardware(
+                    &iso.stabilizers,
+                    &hardware,
+                    &metric,
+                    100,
+                );
+                check_connectivity(&circuit, &hardware);
+                let mut check_iso = IsometryTableau::new(0, 9);
+                check_iso.conjugate_with_circuit(&circuit);
+                check_iso.normalize_inplace();
+                assert_eq!(check_iso, iso);
+            }
+        }
+    }
+}
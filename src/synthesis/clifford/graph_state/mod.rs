@@ -1,5 +1,7 @@
 pub mod count;
 pub mod depth;
+pub mod hardware;
 pub mod synthesis;
 pub mod utils;
+pub use hardware::{synthesize_graph_state_on_hardware, synthesize_stabilizer_state_on_hardware};
 pub use synthesis::{synthesize_graph_state, synthesize_stabilizer_state};
@@ -0,0 +1,149 @@
+//! Memoization of isometry synthesis.
+//!
+//! Synthesizing the same isometry many times (e.g. the Clifford corrections of a circuit made of
+//! repeated blocks) can be avoided by storing the synthesized circuits in a [SynthesisCache].
+//!
+//! Caching is provided by a separate entry point, [isometry_synthesis_cached], rather than by an
+//! extra parameter of [isometry_synthesis]: the latter keeps its signature, which is used
+//! throughout the crate and by downstream code, and the cache is only ever paid for (hashing
+//! and cloning the isometry) by callers that opt in.
+use super::synthesis::isometry_synthesis;
+use crate::structures::{CliffordCircuit, IsometryTableau, Metric};
+use std::collections::HashMap;
+
+/// A cache of synthesized circuits, keyed by isometry, metric and number of iterations
+///
+/// Isometries are compared through their canonical form (see [IsometryTableau::canonical]), so
+/// that the choice of stabilizer generators does not matter.
+#[derive(Debug, Clone, Default)]
+pub struct SynthesisCache {
+    circuits: HashMap<(IsometryTableau, Metric, usize), CliffordCircuit>,
+}
+
+impl SynthesisCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Returns the number of cached circuits
+    pub fn len(&self) -> usize {
+        self.circuits.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.circuits.is_empty()
+    }
+    pub fn clear(&mut self) {
+        self.circuits.clear();
+    }
+    /// Returns the cached circuit for an isometry, if any
+    pub fn get(
+        &self,
+        isometry: &IsometryTableau,
+        metric: &Metric,
+        niter: usize,
+    ) -> Option<&CliffordCircuit> {
+        self.circuits
+            .get(&(isometry.canonical(), metric.clone(), niter))
+    }
+    /// Stores a circuit implementing an isometry
+    pub fn insert(
+        &mut self,
+        isometry: &IsometryTableau,
+        metric: &Metric,
+        niter: usize,
+        circuit: CliffordCircuit,
+    ) {
+        self.circuits
+            .insert((isometry.canonical(), metric.clone(), niter), circuit);
+    }
+}
+
+/// Same as [isometry_synthesis] but first looks up the cache, and stores the result on a miss
+pub fn isometry_synthesis_cached(
+    isometry: &IsometryTableau,
+    metric: &Metric,
+    niter: usize,
+    cache: &mut SynthesisCache,
+) -> CliffordCircuit {
+    if let Some(circuit) = cache.get(isometry, metric, niter) {
+        return circuit.clone();
+    }
+    let circuit = isometry_synthesis(isometry, metric, niter);
+    cache.insert(isometry, metric, niter, circuit.clone());
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::{PauliSet, Tableau};
+    use crate::verification::clifford::product;
+    use crate::verification::{check_circuit_isometry, compare_isometries, Equivalence};
+
+    #[test]
+    fn test_cache() {
+        let mut cache = SynthesisCache::new();
+        let isometry = Tableau::random_uniform(6, 0).to_isometry();
+        let circuit = isometry_synthesis_cached(&isometry, &Metric::COUNT, 1, &mut cache);
+        check_circuit_isometry(&circuit, &isometry, Equivalence::EXACT).unwrap();
+        assert_eq!(cache.len(), 1);
+
+        // Same operator with a different internal layout
+        let mut shifted = isometry.clone();
+        shifted.logicals = PauliSet::new(6);
+        shifted.logicals.insert(&"I".repeat(6), false);
+        for i in 0..isometry.logicals.len() {
+            let (phase, string) = isometry.logicals.get(i);
+            shifted.logicals.insert(&string, phase);
+        }
+        shifted.logicals.pop();
+        let cached = isometry_synthesis_cached(&shifted, &Metric::COUNT, 1, &mut cache);
+        assert_eq!(cached.gates, circuit.gates);
+        assert_eq!(cache.len(), 1);
+
+        isometry_synthesis_cached(&isometry, &Metric::DEPTH, 1, &mut cache);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&isometry, &Metric::COUNT, 2).is_none());
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_cache_up_to_stabilizers() {
+        for seed in 0..5 {
+            let mut cache = SynthesisCache::new();
+            let isometry = IsometryTableau::random_uniform(3, 2, seed);
+            let circuit = isometry_synthesis_cached(&isometry, &Metric::COUNT, 1, &mut cache);
+
+            // Multiplying stabilizers into a logical operator and into another stabilizer
+            let logicals: Vec<_> = (0..6).map(|i| isometry.logicals.get(i)).collect();
+            let stabilizers: Vec<_> = (0..2).map(|i| isometry.stabilizers.get(i)).collect();
+            let mut modified = isometry.clone();
+            modified.logicals = PauliSet::new(5);
+            modified.stabilizers = PauliSet::new(5);
+            let (phase, string) = product(&logicals[0], &stabilizers[1]).unwrap();
+            modified.logicals.insert(&string, phase);
+            for (phase, string) in logicals.iter().skip(1) {
+                modified.logicals.insert(string, *phase);
+            }
+            let (phase, string) = product(&stabilizers[0], &stabilizers[1]).unwrap();
+            modified
+                .stabilizers
+                .insert(&stabilizers[1].1, stabilizers[1].0);
+            modified.stabilizers.insert(&string, phase);
+            assert_ne!(modified, isometry);
+            compare_isometries(&isometry, &modified, Equivalence::UP_TO_STABILIZERS).unwrap();
+            assert_eq!(modified.canonical(), isometry.canonical());
+            let cached = isometry_synthesis_cached(&modified, &Metric::COUNT, 1, &mut cache);
+            assert_eq!(cached.gates, circuit.gates);
+            assert_eq!(cache.len(), 1);
+
+            // Flipping the sign of a logical operator changes the isometry
+            let mut flipped = modified.clone();
+            flipped.logicals.set_phase(2, !logicals[2].0);
+            assert!(
+                compare_isometries(&isometry, &flipped, Equivalence::UP_TO_STABILIZERS).is_err()
+            );
+            assert!(cache.get(&flipped, &Metric::COUNT, 1).is_none());
+        }
+    }
+}
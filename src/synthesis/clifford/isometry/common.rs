@@ -110,14 +110,11 @@ mod tests {
     use crate::structures::IsometryTableau;
 
     fn is_symmetric(table: &Matrix) -> bool {
-        for i in 0..table.len() {
-            for j in 0..table.len() {
-                if table[j][i] != table[i][j] {
-                    return false;
-                }
-            }
-        }
-        true
+        table.iter().enumerate().all(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .all(|(j, entry)| *entry == table[j][i])
+        })
     }
     #[test]
     fn test_decompose() {
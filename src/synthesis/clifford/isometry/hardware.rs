@@ -0,0 +1,141 @@
+//! Synthesis of isometries under connectivity constraints.
+//!
+//! The isometry is reduced to the trivial one qubit by qubit. A data qubit is freed by folding
+//! its Z logical operator, then its X logical operator, onto it. An ancilla is freed by folding
+//! the stabilizer with the cheapest Steiner tree onto a Z on it: the other operators are then
+//! multiplied by this stabilizer so that they act trivially on the ancilla. The phases of the
+//! best reduction circuit are fixed at the end.
+use super::common::fix_phases;
+use crate::routines::tree_folding::{
+    best_elimination, cheapest_steiner_edges, fold_along_tree, steiner_edges_within, with_terminal,
+    FoldBasis,
+};
+use crate::structures::{
+    CliffordCircuit, HardwareGraph, IsometryTableau, Metric, PauliLike, PauliSet,
+};
+
+/// Folds operator `index` of `pauli_set` onto `qbit` using the qubits in `alive`
+fn fold_operator(
+    pauli_set: &PauliSet,
+    index: usize,
+    qbit: usize,
+    basis: FoldBasis,
+    hardware: &HardwareGraph,
+    alive: &[usize],
+) -> CliffordCircuit {
+    let terminals = with_terminal(pauli_set.get_support(index), qbit);
+    let edges = steiner_edges_within(hardware, alive, &terminals);
+    fold_along_tree(pauli_set, index, &edges, qbit, basis)
+}
+
+/// Reduces the isometry to the trivial one, eliminating the qubits in the given order.
+/// Returns the reduction circuit.
+fn reduce_isometry(
+    isometry: &IsometryTableau,
+    hardware: &HardwareGraph,
+    order: &[usize],
+) -> CliffordCircuit {
+    let n = isometry.n;
+    let mut work = isometry.clone();
+    let mut circuit = CliffordCircuit::new(n + isometry.k);
+    let mut alive: Vec<usize> = (0..n + isometry.k).collect();
+    let mut used_stabilizers = vec![false; isometry.k];
+    for qbit in order.iter() {
+        if *qbit < n {
+            let piece = fold_operator(
+                &work.logicals,
+                n + qbit,
+                *qbit,
+                FoldBasis::Z,
+                hardware,
+                &alive,
+            );
+            work.conjugate_with_circuit(&piece);
+            circuit.extend_with(&piece);
+            let piece = fold_operator(&work.logicals, *qbit, *qbit, FoldBasis::X, hardware, &alive);
+            work.conjugate_with_circuit(&piece);
+            circuit.extend_with(&piece);
+        } else {
+            let candidates = (0..isometry.k)
+                .filter(|i| !used_stabilizers[*i])
+                .map(|i| (i, with_terminal(work.stabilizers.get_support(i), *qbit)));
+            let (index, edges) = cheapest_steiner_edges(hardware, &alive, candidates).unwrap();
+            let piece = fold_along_tree(&work.stabilizers, index, &edges, *qbit, FoldBasis::Z);
+            work.conjugate_with_circuit(&piece);
+            circuit.extend_with(&piece);
+            used_stabilizers[index] = true;
+            // All the other operators commute with Z on `qbit`: we multiply them by the
+            // stabilizer in order to clear their Z component on `qbit`
+            for i in 0..isometry.k {
+                if i != index {
+                    work.stabilizers.set_entry(i, *qbit, false, false);
+                }
+            }
+            for i in 0..2 * n {
+                work.logicals.set_entry(i, *qbit, false, false);
+            }
+        }
+        alive.retain(|q| q != qbit);
+    }
+    circuit
+}
+
+/// Synthesizes an isometry using only two-qubit gates acting on coupled qubits.
+///
+/// The qubits of the isometry are identified with the qubits of the hardware graph, which is
+/// assumed to be connected. Up to `niter` elimination orders (one per BFS root) are tried and
+/// the best circuit according to `metric` is returned.
+pub fn isometry_synthesis_on_hardware(
+    isometry: &IsometryTableau,
+    hardware: &HardwareGraph,
+    metric: &Metric,
+    niter: usize,
+) -> CliffordCircuit {
+    assert_eq!(
+        isometry.n + isometry.k,
+        hardware.len(),
+        "The hardware graph and the isometry should have the same number of qubits"
+    );
+    let mut circuit = best_elimination(hardware, metric, niter, |order| {
+        reduce_isometry(isometry, hardware, order).dagger()
+    });
+    fix_phases(isometry, &mut circuit);
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::Tableau;
+    use crate::synthesis::clifford::test_utils::check_connectivity;
+
+    #[test]
+    fn test_clifford_on_hardware() {
+        let hardware = HardwareGraph::grid(3, 3);
+        for metric in [Metric::COUNT, Metric::DEPTH] {
+            for _ in 0..10 {
+                let tableau = Tableau::random(9).to_isometry();
+                let circuit = isometry_synthesis_on_hardware(&tableau, &hardware, &metric, 3);
+                check_connectivity(&circuit, &hardware);
+                let mut simulated = IsometryTableau::new(9, 0);
+                simulated.conjugate_with_circuit(&circuit);
+                assert_eq!(simulated, tableau);
+            }
+        }
+    }
+
+    #[test]
+    fn test_isometry_on_hardware() {
+        let hardware = HardwareGraph::grid(3, 3);
+        for _ in 0..10 {
+            let mut isometry = IsometryTableau::random(4, 5);
+            let circuit = isometry_synthesis_on_hardware(&isometry, &hardware, &Metric::COUNT, 1);
+            check_connectivity(&circuit, &hardware);
+            isometry.normalize_inplace();
+            let mut simulated = IsometryTableau::new(4, 5);
+            simulated.conjugate_with_circuit(&circuit);
+            simulated.normalize_inplace();
+            assert_eq!(simulated, isometry);
+        }
+    }
+}
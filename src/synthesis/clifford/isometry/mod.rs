@@ -1,5 +1,9 @@
+pub mod cache;
 pub mod common;
 pub mod count;
 pub mod depth;
+pub mod hardware;
 pub mod synthesis;
+pub use cache::{isometry_synthesis_cached, SynthesisCache};
+pub use hardware::isometry_synthesis_on_hardware;
 pub use synthesis::isometry_synthesis;
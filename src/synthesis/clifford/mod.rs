@@ -1,8 +1,11 @@
 //! This module contains all the Clifford circuit synthesis algorithms.
 //!
-//! * [Clifford and Clifford isometry synthesis ](isometry::isometry_synthesis)
-//! * [Pauli operators codiagonalization](codiagonalization::codiagonalize)
-//! * [Graph states](graph_state::synthesize_graph_state) and [stabilizer states synthesis](graph_state::synthesize_stabilizer_state)
+//! * [Clifford and Clifford isometry synthesis ](isometry::isometry_synthesis), also available
+//!   [under connectivity constraints](isometry::isometry_synthesis_on_hardware)
+//! * [Pauli operators codiagonalization](codiagonalization::codiagonalize), also available
+//!   [under connectivity constraints](codiagonalization::codiagonalize_on_hardware)
+//! * [Graph states](graph_state::synthesize_graph_state) and [stabilizer states synthesis](graph_state::synthesize_stabilizer_state),
+//!   also available [under connectivity constraints](graph_state::synthesize_graph_state_on_hardware)
 //!
 //! # Clifford synthesis examples
 //!
@@ -50,6 +53,18 @@
 //! assert_eq!(circuit.cnot_count(), 0);
 //! ```
 //!
+//! When targeting a device with limited connectivity, the hardware-aware variant only uses
+//! two-qubit gates acting on coupled qubits:
+//!
+//! ```
+//! use rustiq_core::structures::{HardwareGraph, Metric, Tableau};
+//! use rustiq_core::synthesis::clifford::isometry::isometry_synthesis_on_hardware;
+//! // A line 0 - 1 - 2 - 3
+//! let hardware = HardwareGraph::from_couplings(&[(0, 1), (1, 2), (2, 3)]);
+//! let my_tab = Tableau::random(4);
+//! let circuit = isometry_synthesis_on_hardware(&my_tab.to_isometry(), &hardware, &Metric::COUNT, 4);
+//! ```
+//!
 //! # Pauli codiagonalization example
 //!
 //! This algorithms acts on a set of pairwise commuting Pauli operators.
@@ -78,3 +93,5 @@
 pub mod codiagonalization;
 pub mod graph_state;
 pub mod isometry;
+#[cfg(test)]
+mod test_utils;
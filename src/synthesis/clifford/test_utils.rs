@@ -0,0 +1,10 @@
+use crate::structures::{CliffordCircuit, CliffordGate, HardwareGraph};
+
+/// Checks that all the two-qubit gates of `circuit` act on coupled qubits
+pub fn check_connectivity(circuit: &CliffordCircuit, hardware: &HardwareGraph) {
+    for gate in circuit.gates.iter() {
+        if let CliffordGate::CNOT(i, j) | CliffordGate::CZ(i, j) = gate {
+            assert!(hardware.are_connected(*i, *j));
+        }
+    }
+}
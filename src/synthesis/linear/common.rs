@@ -0,0 +1,93 @@
+//! Conversions between linear reversible operators and CNOT circuits.
+//!
+//! An invertible matrix `M` over F2 describes the linear reversible operator `x -> Mx`. A gate
+//! `CNOT(i, j)` implements `x_j ^= x_i`, i.e. the row operation adding row `i` to row `j`.
+use crate::routines::f2_linalg::{rowop, Matrix};
+use crate::structures::{CliffordCircuit, CliffordGate};
+
+/// Returns the identity matrix of size `n`
+pub fn identity(n: usize) -> Matrix {
+    let mut matrix = vec![vec![false; n]; n];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        row[i] = true;
+    }
+    matrix
+}
+
+/// Computes the matrix of the linear reversible operator implemented by a circuit.
+///
+/// Panics if the circuit contains gates other than CNOT gates.
+pub fn circuit_to_matrix(circuit: &CliffordCircuit) -> Matrix {
+    let mut matrix = identity(circuit.nqbits);
+    for gate in circuit.gates.iter() {
+        match gate {
+            CliffordGate::CNOT(i, j) => rowop(&mut matrix, *i, *j),
+            _ => panic!("Gate {:?} is not a CNOT gate", gate),
+        }
+    }
+    matrix
+}
+
+/// Builds the circuit implementing a matrix `M` from a reduction of `M` to the identity.
+///
+/// `row_ops` and `col_ops` are sequences of pairs `(i, j)` adding row (resp. column) `i` to row
+/// (resp. column) `j`, such that applying them to `M` (in any interleaving) yields the identity.
+pub(crate) fn reduction_to_circuit(
+    n: usize,
+    row_ops: &[(usize, usize)],
+    col_ops: &[(usize, usize)],
+) -> CliffordCircuit {
+    let mut circuit = CliffordCircuit::new(n);
+    for (i, j) in col_ops.iter() {
+        circuit.gates.push(CliffordGate::CNOT(*j, *i));
+    }
+    for (i, j) in row_ops.iter().rev() {
+        circuit.gates.push(CliffordGate::CNOT(*i, *j));
+    }
+    circuit
+}
+
+/// Returns true if `matrix` is the identity
+pub(crate) fn is_identity(matrix: &Matrix) -> bool {
+    matrix
+        .iter()
+        .enumerate()
+        .all(|(i, row)| row.iter().enumerate().all(|(j, b)| *b == (i == j)))
+}
+
+/// Generates a random invertible matrix by applying random row operations to the identity
+#[cfg(test)]
+pub(crate) fn random_invertible(n: usize) -> Matrix {
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+    let mut matrix = identity(n);
+    for _ in 0..n * n {
+        let i = rng.gen_range(0..n);
+        let j = rng.gen_range(0..n);
+        if i != j {
+            rowop(&mut matrix, i, j);
+        }
+    }
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routines::f2_linalg::colop;
+
+    #[test]
+    fn test_reduction_to_circuit() {
+        let mut matrix = identity(3);
+        rowop(&mut matrix, 0, 1);
+        rowop(&mut matrix, 1, 2);
+        let target = matrix.clone();
+        let row_ops = [(1, 2)];
+        let col_ops = [(1, 0)];
+        rowop(&mut matrix, 1, 2);
+        colop(&mut matrix, 1, 0);
+        assert!(is_identity(&matrix));
+        let circuit = reduction_to_circuit(3, &row_ops, &col_ops);
+        assert_eq!(circuit_to_matrix(&circuit), target);
+    }
+}
@@ -1,62 +1,84 @@
-use crate::routines::f2_linalg::{Matrix, lu_facto, row_op};
-
+//! Greedy Gaussian elimination.
+//!
+//! The matrix is first LU-decomposed. Each triangular factor is then reduced to the identity by
+//! greedily picking the row operation that removes the largest number of ones.
+use super::common::{is_identity, reduction_to_circuit};
+use crate::routines::f2_linalg::{lu_facto, rowop, Matrix};
+use crate::structures::{CliffordCircuit, CliffordGate};
 
+fn weight(row: &[bool]) -> usize {
+    row.iter().filter(|b| **b).count()
+}
 
-fn select_rows(matrix: &Matrix, offset: usize) -> (usize, usize) {
-    let mut rows: Vec<usize> = offset..matrix.len().collect();
-    for k in offset..matrix.len() {
-        let mut set_0 = Vec::new();
-        let mut set_1 = Vec::new();
-        for row in rows {
-            if matrix[row][k]{
-                set_1.push(row);
-            } else {
-                set_0.push(row);
+/// Picks the row operation `(a, b)` (adding row `a` to row `b`) reducing the most the number of
+/// ones in a triangular matrix, among the operations preserving its shape. Returns None if no such
+/// row operation reduces the number of ones.
+fn select_rows(matrix: &Matrix, lower: bool) -> Option<(usize, usize)> {
+    let mut best = None;
+    let mut best_gain = 0;
+    for b in 0..matrix.len() {
+        let current = weight(&matrix[b]);
+        for a in 0..matrix.len() {
+            if a == b || (a < b) != lower {
+                continue;
+            }
+            let new_weight = matrix[a]
+                .iter()
+                .zip(matrix[b].iter())
+                .filter(|(x, y)| *x ^ *y)
+                .count();
+            if new_weight < current && current - new_weight > best_gain {
+                best_gain = current - new_weight;
+                best = Some((a, b));
             }
-        }
-        if set_1.len() > 1 {
-            rows = set_1;
-        }else{
-            rows = set_0;
-        }
-        if rows.len() == 2 {
-            break;
         }
     }
-    return (rows[0], rows[1]);
+    best
 }
 
-
-fn greedy_ge_l(matrix: &Matrix) -> Vec<(usize, usize)> {
+/// Reduces a unit lower (or upper) triangular matrix to the identity.
+///
+/// Such a matrix always admits a row operation decreasing its number of ones: if `c` is the
+/// first (or last) column containing an off-diagonal one, row `c` is a unit vector.
+fn greedy_ge_triangular(matrix: &Matrix, lower: bool) -> Vec<(usize, usize)> {
     let mut matrix = matrix.clone();
     let mut moves = Vec::new();
-    loop {
-        let (a, b) = select_rows(&matrix, 0);
+    while !is_identity(&matrix) {
+        let (a, b) = select_rows(&matrix, lower).expect("The matrix should be unit triangular");
+        rowop(&mut matrix, a, b);
         moves.push((a, b));
-        row_op(&mut matrix, a, b);
-        let mut is_identity = true;
-        for i in 0..matrix.len() {
-            for j in 0..i {
-                if matrix[i][j] {
-                    is_identity = false;
-                    break
-                }
-            }
-            if !is_identity {
-                break;
-            }
-        }
-        if !is_identity {
-            continue;
-        }
-        break;
     }
     moves
 }
 
-pub fn greedy_ge(matrix: &Matrix) -> Vec<(usize, usize)> {
-    let (l, u, _, _) = lu_facto(matrix);
-    let mut moves = greedy_ge_l(&l);
-    moves.extend(greedy_ge_l(&u));
-    moves
-} 
\ No newline at end of file
+/// Synthesizes a CNOT circuit implementing an invertible matrix using greedy Gaussian elimination
+pub fn greedy_ge(matrix: &Matrix) -> CliffordCircuit {
+    let (l, u, _, ops) = lu_facto(matrix);
+    // `lu_facto` stores the row operation adding row `a` to row `b` as CNOT(b, a)
+    let mut moves: Vec<(usize, usize)> = ops
+        .gates
+        .iter()
+        .map(|gate| match gate {
+            CliffordGate::CNOT(b, a) => (*a, *b),
+            _ => unreachable!(),
+        })
+        .collect();
+    moves.extend(greedy_ge_triangular(&l, true));
+    moves.extend(greedy_ge_triangular(&u, false));
+    reduction_to_circuit(matrix.len(), &moves, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::common::{circuit_to_matrix, random_invertible};
+    use super::*;
+
+    #[test]
+    fn test_greedy_ge() {
+        for _ in 0..10 {
+            let matrix = random_invertible(12);
+            let synthesized = greedy_ge(&matrix);
+            assert_eq!(circuit_to_matrix(&synthesized), matrix);
+        }
+    }
+}
@@ -1,2 +1,37 @@
+//! This module contains the synthesis algorithms for linear reversible operators, i.e. CNOT
+//! circuits.
+//!
+//! A linear reversible operator over `n` qubits is described by an invertible `n x n` matrix `M`
+//! over F2, mapping the computational basis state `|x>` to `|Mx>`. A gate `CNOT(i, j)` adds row
+//! `i` to row `j`.
+//!
+//! * [Patel-Markov-Hayes](pmh::pmh_synthesis)
+//! * [Greedy Gaussian elimination](greedy_ge::greedy_ge)
+//! * [Syndrome decoding](syndrome::syndrome_synthesis), targeting CNOT count
+//!
+//! Method [linear_synthesis] runs all of them and returns the best circuit according to a `Metric`.
+//! For a handful of qubits, [OptimalLinearSynthesizer] produces circuits with a provably minimal
+//! number of CNOT gates.
+//!
+//! ```
+//! use rustiq_core::structures::{CliffordCircuit, CliffordGate, Metric};
+//! use rustiq_core::synthesis::linear::{circuit_to_matrix, linear_synthesis};
+//! // A sub-optimal implementation of a SWAP gate followed by a CNOT gate
+//! let mut my_circuit = CliffordCircuit::new(3);
+//! for (i, j) in [(0, 1), (1, 0), (0, 1), (2, 0), (2, 0), (0, 1)] {
+//!     my_circuit.gates.push(CliffordGate::CNOT(i, j));
+//! }
+//! let matrix = circuit_to_matrix(&my_circuit);
+//! let circuit = linear_synthesis(&matrix, &Metric::COUNT, 10);
+//! assert_eq!(circuit_to_matrix(&circuit), matrix);
+//! assert!(circuit.cnot_count() <= 4);
+//! ```
+pub mod common;
 pub mod greedy_ge;
-pub mod syndrome;
\ No newline at end of file
+pub mod optimal;
+pub mod pmh;
+pub mod syndrome;
+pub mod synthesis;
+pub use common::circuit_to_matrix;
+pub use optimal::OptimalLinearSynthesizer;
+pub use synthesis::linear_synthesis;
@@ -0,0 +1,413 @@
+//! Exact CNOT count minimization for small linear reversible operators.
+//!
+//! A breadth-first search from the identity enumerates all the operators that can be implemented
+//! with at most `radius` CNOT gates. The resulting table is then used in a meet-in-the-middle
+//! search: a second breadth-first search starts from the target operator, until it reaches an
+//! operator of the table. Since any circuit of minimal size `L > radius` goes through an operator
+//! at distance exactly `radius` from the identity after `L - radius` gates, the first level of the
+//! second search hitting the table provides a provably minimal circuit.
+//!
+//! When `radius` is at least the diameter of `GL(n, 2)` (e.g. 6 for 3 qubits, 9 for 4 qubits),
+//! the table contains all the operators and the synthesis is a simple lookup. For 5 (resp. 6)
+//! qubits, a radius of 6 (resp. 5) yields a table of about 700k (resp. 1.1M) operators, that can
+//! be cached on disk with [OptimalLinearSynthesizer::save].
+use super::common::identity;
+use super::synthesis::linear_synthesis;
+use crate::routines::f2_linalg::Matrix;
+use crate::structures::{CliffordCircuit, CliffordGate, Metric};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+
+/// Largest number of qubits such that an operator fits in a `u64`
+const MAX_QUBITS: usize = 8;
+/// Marker of the identity in the parent tables
+const ROOT: u8 = u8::MAX;
+/// Magic bytes at the start of a table file
+const MAGIC: &[u8; 8] = b"RQLINOPT";
+/// Size in bytes of the header of a table file: magic bytes, n, radius and number of entries
+const HEADER_SIZE: u64 = 8 + 1 + 8 + 8;
+/// Size in bytes of a table entry: an operator and a move index
+const ENTRY_SIZE: u64 = 8 + 1;
+
+/// Exact synthesis of linear reversible operators over a fixed number of qubits
+pub struct OptimalLinearSynthesizer {
+    n: usize,
+    radius: usize,
+    /// The CNOT gates, indexed by the bytes stored in `table`
+    moves: Vec<(usize, usize)>,
+    /// Maps each operator within `radius` CNOT gates of the identity to the index of a move
+    /// bringing it one step closer to the identity
+    table: HashMap<u64, u8>,
+}
+
+impl OptimalLinearSynthesizer {
+    /// Enumerates the operators over `n` qubits implementable with at most `radius` CNOT gates
+    pub fn new(n: usize, radius: usize) -> Self {
+        assert!(
+            n <= MAX_QUBITS,
+            "Exact synthesis is limited to {} qubits",
+            MAX_QUBITS
+        );
+        let moves = all_moves(n);
+        let mut table = HashMap::new();
+        let start = encode(&identity(n));
+        table.insert(start, ROOT);
+        let mut frontier = vec![start];
+        for _ in 0..radius {
+            let mut next = Vec::new();
+            for op in frontier.iter() {
+                for (index, (i, j)) in moves.iter().enumerate() {
+                    let new_op = apply_move(*op, n, *i, *j);
+                    if let Entry::Vacant(entry) = table.entry(new_op) {
+                        entry.insert(index as u8);
+                        next.push(new_op);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        Self {
+            n,
+            radius,
+            moves,
+            table,
+        }
+    }
+
+    /// The number of qubits of the operators handled by this synthesizer
+    pub fn nqbits(&self) -> usize {
+        self.n
+    }
+
+    /// The radius of the table
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    /// The number of operators stored in the table
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns true if the table is empty (this never happens)
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Returns the minimal number of CNOT gates required to implement `matrix`, if it is at most
+    /// the radius of the table
+    pub fn table_distance(&self, matrix: &Matrix) -> Option<usize> {
+        self.reduce_with_table(encode(matrix))
+            .map(|gates| gates.len())
+    }
+
+    /// Follows the table from `op` to the identity. Returns the corresponding row operations.
+    fn reduce_with_table(&self, mut op: u64) -> Option<Vec<(usize, usize)>> {
+        let mut moves = Vec::new();
+        loop {
+            let index = *self.table.get(&op)?;
+            if index == ROOT {
+                return Some(moves);
+            }
+            let (i, j) = self.moves[index as usize];
+            op = apply_move(op, self.n, i, j);
+            moves.push((i, j));
+        }
+    }
+
+    /// Synthesizes a CNOT circuit implementing an invertible matrix.
+    ///
+    /// The meet-in-the-middle search visits at most `max_nodes` operators. If it fails to reach
+    /// the table, the best heuristic circuit is returned instead. The returned boolean is true
+    /// if the circuit is provably CNOT-optimal.
+    pub fn synthesize(&self, matrix: &Matrix, max_nodes: usize) -> (CliffordCircuit, bool) {
+        assert_eq!(
+            matrix.len(),
+            self.n,
+            "The matrix should act on {} qubits",
+            self.n
+        );
+        let target = encode(matrix);
+        // Maps each visited operator to the index of the move leading to it from its parent
+        let mut parents: HashMap<u64, u8> = HashMap::new();
+        parents.insert(target, ROOT);
+        let mut frontier = vec![target];
+        loop {
+            let hit = frontier
+                .iter()
+                .filter_map(|op| Some((*op, self.reduce_with_table(*op)?)))
+                .min_by_key(|(_, moves)| moves.len());
+            if let Some((op, table_moves)) = hit {
+                return (self.build_circuit(op, &table_moves, &parents), true);
+            }
+            if frontier.is_empty() || parents.len() > max_nodes {
+                break;
+            }
+            let mut next = Vec::new();
+            for op in frontier.iter() {
+                for (index, (i, j)) in self.moves.iter().enumerate() {
+                    let new_op = apply_move(*op, self.n, *i, *j);
+                    if let Entry::Vacant(entry) = parents.entry(new_op) {
+                        entry.insert(index as u8);
+                        next.push(new_op);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        (linear_synthesis(matrix, &Metric::COUNT, 10), false)
+    }
+
+    /// Builds the circuit implementing the target operator, given an operator `op` of the table
+    /// reached from the target with the moves stored in `parents`
+    fn build_circuit(
+        &self,
+        mut op: u64,
+        table_moves: &[(usize, usize)],
+        parents: &HashMap<u64, u8>,
+    ) -> CliffordCircuit {
+        let mut circuit = CliffordCircuit::new(self.n);
+        // `op` is the product of the table moves, the last one being applied first
+        for (i, j) in table_moves.iter().rev() {
+            circuit.gates.push(CliffordGate::CNOT(*i, *j));
+        }
+        // The target is obtained from `op` by undoing the moves of the search, last one first
+        loop {
+            let index = parents[&op];
+            if index == ROOT {
+                break;
+            }
+            let (i, j) = self.moves[index as usize];
+            circuit.gates.push(CliffordGate::CNOT(i, j));
+            op = apply_move(op, self.n, i, j);
+        }
+        circuit
+    }
+
+    /// Writes the table to a file
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| format!("Unable to create `{}`: {}", path, e))?;
+        let mut writer = BufWriter::new(file);
+        let mut write = |bytes: &[u8]| {
+            writer
+                .write_all(bytes)
+                .map_err(|e| format!("Unable to write to `{}`: {}", path, e))
+        };
+        write(MAGIC)?;
+        write(&[self.n as u8])?;
+        write(&(self.radius as u64).to_le_bytes())?;
+        write(&(self.table.len() as u64).to_le_bytes())?;
+        for (op, index) in self.table.iter() {
+            write(&op.to_le_bytes())?;
+            write(&[*index])?;
+        }
+        writer
+            .flush()
+            .map_err(|e| format!("Unable to write to `{}`: {}", path, e))
+    }
+
+    /// Reads a table written by [OptimalLinearSynthesizer::save]
+    pub fn load(path: &str) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Unable to open `{}`: {}", path, e))?;
+        let file_size = file
+            .metadata()
+            .map_err(|e| format!("Unable to read `{}`: {}", path, e))?
+            .len();
+        let mut reader = BufReader::new(file);
+        let mut read = |buffer: &mut [u8]| {
+            reader
+                .read_exact(buffer)
+                .map_err(|e| format!("Unable to read `{}`: {}", path, e))
+        };
+        let mut magic = [0u8; 8];
+        read(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(format!("`{}` is not a linear synthesis table", path));
+        }
+        let mut byte = [0u8; 1];
+        let mut word = [0u8; 8];
+        read(&mut byte)?;
+        let n = byte[0] as usize;
+        if n > MAX_QUBITS {
+            return Err(format!("Invalid number of qubits {} in `{}`", n, path));
+        }
+        read(&mut word)?;
+        let radius = u64::from_le_bytes(word) as usize;
+        read(&mut word)?;
+        let size = u64::from_le_bytes(word);
+        // Checking the size before allocating the table
+        if size > gl_order(n) || size.checked_mul(ENTRY_SIZE) != Some(file_size - HEADER_SIZE) {
+            return Err(format!("Invalid table size {} in `{}`", size, path));
+        }
+        let size = size as usize;
+        let moves = all_moves(n);
+        let mut table = HashMap::with_capacity(size);
+        for _ in 0..size {
+            read(&mut word)?;
+            read(&mut byte)?;
+            if byte[0] != ROOT && byte[0] as usize >= moves.len() {
+                return Err(format!("Invalid move index {} in `{}`", byte[0], path));
+            }
+            table.insert(u64::from_le_bytes(word), byte[0]);
+        }
+        Ok(Self {
+            n,
+            radius,
+            moves,
+            table,
+        })
+    }
+
+    /// Loads the table from `path` if it exists and matches `n` and `radius`. Otherwise, builds
+    /// the table and writes it to `path`. Fails if an existing file cannot be read.
+    pub fn load_or_build(path: &str, n: usize, radius: usize) -> Result<Self, String> {
+        match std::fs::metadata(path) {
+            Ok(_) => {
+                let synthesizer = Self::load(path)?;
+                if synthesizer.n == n && synthesizer.radius == radius {
+                    return Ok(synthesizer);
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Unable to access `{}`: {}", path, e)),
+        }
+        let synthesizer = Self::new(n, radius);
+        synthesizer.save(path)?;
+        Ok(synthesizer)
+    }
+}
+
+/// Order of GL(n, 2), i.e. the number of invertible n x n matrices (n <= MAX_QUBITS)
+fn gl_order(n: usize) -> u64 {
+    (0..n).map(|i| (1u64 << n) - (1u64 << i)).product()
+}
+
+/// Lists all the CNOT gates over `n` qubits
+fn all_moves(n: usize) -> Vec<(usize, usize)> {
+    (0..n)
+        .flat_map(|i| (0..n).filter(move |j| *j != i).map(move |j| (i, j)))
+        .collect()
+}
+
+/// Packs a matrix in a `u64`, row `i` occupying bits `i * n` to `(i + 1) * n - 1`
+fn encode(matrix: &Matrix) -> u64 {
+    let n = matrix.len();
+    let mut op = 0;
+    for (i, row) in matrix.iter().enumerate() {
+        for (j, b) in row.iter().enumerate() {
+            if *b {
+                op |= 1 << (i * n + j);
+            }
+        }
+    }
+    op
+}
+
+/// Adds row `i` to row `j`
+fn apply_move(op: u64, n: usize, i: usize, j: usize) -> u64 {
+    let mask = (1 << n) - 1;
+    op ^ (((op >> (i * n)) & mask) << (j * n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::common::{circuit_to_matrix, random_invertible};
+    use super::*;
+
+    #[test]
+    fn test_full_table() {
+        // |GL(3, 2)| = 168
+        let synthesizer = OptimalLinearSynthesizer::new(3, 100);
+        assert_eq!(synthesizer.len(), 168);
+        for _ in 0..10 {
+            let matrix = random_invertible(3);
+            let (circuit, optimal) = synthesizer.synthesize(&matrix, 0);
+            assert!(optimal);
+            assert_eq!(circuit_to_matrix(&circuit), matrix);
+        }
+    }
+
+    #[test]
+    fn test_swap() {
+        let synthesizer = OptimalLinearSynthesizer::new(2, 1);
+        let matrix = vec![vec![false, true], vec![true, false]];
+        let (circuit, optimal) = synthesizer.synthesize(&matrix, 100);
+        assert!(optimal);
+        assert_eq!(circuit.gates.len(), 3);
+        assert_eq!(circuit_to_matrix(&circuit), matrix);
+    }
+
+    #[test]
+    fn test_meet_in_the_middle() {
+        let full = OptimalLinearSynthesizer::new(4, 100);
+        assert_eq!(full.len(), 20160);
+        let partial = OptimalLinearSynthesizer::new(4, 3);
+        for _ in 0..20 {
+            let matrix = random_invertible(4);
+            let (circuit, optimal) = partial.synthesize(&matrix, 100000);
+            assert!(optimal);
+            assert_eq!(circuit_to_matrix(&circuit), matrix);
+            assert_eq!(Some(circuit.gates.len()), full.table_distance(&matrix));
+            let heuristic = linear_synthesis(&matrix, &Metric::COUNT, 10);
+            assert!(heuristic.gates.len() >= circuit.gates.len());
+        }
+    }
+
+    #[test]
+    fn test_search_limit() {
+        let synthesizer = OptimalLinearSynthesizer::new(4, 0);
+        let matrix = random_invertible(4);
+        let (circuit, optimal) = synthesizer.synthesize(&matrix, 0);
+        assert_eq!(optimal, synthesizer.table_distance(&matrix) == Some(0));
+        assert_eq!(circuit_to_matrix(&circuit), matrix);
+    }
+
+    #[test]
+    fn test_save_load() {
+        let path = std::env::temp_dir().join(format!("rustiq_linear_{}.bin", std::process::id()));
+        let path = path.to_str().unwrap();
+        let synthesizer = OptimalLinearSynthesizer::load_or_build(path, 3, 4).unwrap();
+        let loaded = OptimalLinearSynthesizer::load(path).unwrap();
+        assert_eq!(loaded.nqbits(), 3);
+        assert_eq!(loaded.radius(), 4);
+        assert_eq!(loaded.table, synthesizer.table);
+        std::fs::remove_file(path).unwrap();
+        assert!(OptimalLinearSynthesizer::load(path).is_err());
+    }
+
+    #[test]
+    fn test_load_invalid_files() {
+        let path = std::env::temp_dir().join(format!("rustiq_invalid_{}.bin", std::process::id()));
+        let path = path.to_str().unwrap();
+        let header = |n: u8, size: u64| -> Vec<u8> {
+            let mut bytes = MAGIC.to_vec();
+            bytes.push(n);
+            bytes.extend_from_slice(&3u64.to_le_bytes());
+            bytes.extend_from_slice(&size.to_le_bytes());
+            bytes
+        };
+        // Huge announced sizes are rejected before allocating
+        for (n, size) in [(3, u64::MAX), (3, 169), (8, 1 << 60), (3, 2)] {
+            std::fs::write(path, header(n, size)).unwrap();
+            let error = OptimalLinearSynthesizer::load(path).err().unwrap();
+            assert!(error.starts_with("Invalid table size"), "{}", error);
+        }
+        // Corrupted files are not overwritten
+        std::fs::write(path, b"garbage").unwrap();
+        assert!(OptimalLinearSynthesizer::load_or_build(path, 3, 3).is_err());
+        assert_eq!(std::fs::read(path).unwrap(), b"garbage");
+        // Files built for other parameters are
+        OptimalLinearSynthesizer::new(2, 1).save(path).unwrap();
+        let synthesizer = OptimalLinearSynthesizer::load_or_build(path, 3, 3).unwrap();
+        assert_eq!(
+            OptimalLinearSynthesizer::load(path).unwrap().table,
+            synthesizer.table
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,87 @@
+//! The Patel-Markov-Hayes algorithm.
+//!
+//! Reference: K. N. Patel, I. L. Markov, J. P. Hayes, "Optimal synthesis of linear reversible
+//! circuits", Quantum Information and Computation 8(3), 2008.
+//!
+//! The columns are processed by sections of `section_size` columns. Within a section, rows
+//! sharing the same sub-row pattern are first merged, before performing a standard Gaussian
+//! elimination of the section. This reduces the matrix to an upper triangular matrix, and the
+//! same procedure is then applied to its transpose.
+use super::common::reduction_to_circuit;
+use crate::routines::f2_linalg::{rowop, transpose, Matrix};
+use crate::structures::CliffordCircuit;
+use std::collections::HashMap;
+
+/// Reduces `matrix` to an upper triangular matrix. Returns the row operations used.
+fn lower_cnot_synthesis(matrix: &mut Matrix, section_size: usize) -> Vec<(usize, usize)> {
+    let n = matrix.len();
+    let mut moves = Vec::new();
+    for start in (0..n).step_by(section_size) {
+        let end = (start + section_size).min(n);
+        // Removing duplicate sub-rows
+        let mut patterns: HashMap<Vec<bool>, usize> = HashMap::new();
+        for row in start..n {
+            let pattern = matrix[row][start..end].to_vec();
+            if pattern.iter().all(|b| !*b) {
+                continue;
+            }
+            if let Some(first) = patterns.get(&pattern) {
+                rowop(matrix, *first, row);
+                moves.push((*first, row));
+            } else {
+                patterns.insert(pattern, row);
+            }
+        }
+        // Gaussian elimination of the section
+        for col in start..end {
+            let mut diagonal_one = matrix[col][col];
+            for row in col + 1..n {
+                if matrix[row][col] {
+                    if !diagonal_one {
+                        rowop(matrix, row, col);
+                        moves.push((row, col));
+                        diagonal_one = true;
+                    }
+                    rowop(matrix, col, row);
+                    moves.push((col, row));
+                }
+            }
+        }
+    }
+    moves
+}
+
+/// Synthesizes a CNOT circuit implementing an invertible matrix using the Patel-Markov-Hayes
+/// algorithm with sections of `section_size` columns
+pub fn pmh_synthesis(matrix: &Matrix, section_size: usize) -> CliffordCircuit {
+    assert!(section_size > 0, "The section size should be positive");
+    let mut matrix = matrix.clone();
+    let row_ops = lower_cnot_synthesis(&mut matrix, section_size);
+    // Row operations on the transpose are column operations on the matrix
+    let mut transposed = transpose(&matrix);
+    let col_ops = lower_cnot_synthesis(&mut transposed, section_size);
+    reduction_to_circuit(matrix.len(), &row_ops, &col_ops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::common::{circuit_to_matrix, identity, random_invertible};
+    use super::*;
+
+    #[test]
+    fn test_pmh() {
+        for section_size in 1..5 {
+            for _ in 0..10 {
+                let matrix = random_invertible(13);
+                let circuit = pmh_synthesis(&matrix, section_size);
+                assert_eq!(circuit_to_matrix(&circuit), matrix);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pmh_identity() {
+        let circuit = pmh_synthesis(&identity(5), 2);
+        assert!(circuit.gates.is_empty());
+    }
+}
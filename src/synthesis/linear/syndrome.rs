@@ -0,0 +1,104 @@
+//! Syndrome decoding based count optimization.
+//!
+//! The matrix is first LU-decomposed. A unit lower triangular factor is synthesized one qubit at
+//! a time: qubit `i` only acts as the target of CNOT gates inserted in the circuit implementing
+//! the first `i` rows. Inserting `CNOT(q, i)` at some point of this circuit adds the parity
+//! carried by qubit `q` at this point to row `i`. Picking the smallest set of parities summing to
+//! row `i` is a syndrome decoding problem, solved using information set decoding.
+use crate::routines::decoding::information_set_decoding;
+use crate::routines::f2_linalg::{lu_facto, rowop, Matrix};
+use crate::structures::{CliffordCircuit, CliffordGate};
+
+/// Lists the parities carried by the first `n` qubits along `circuit`, together with the position
+/// at which they are available
+fn gather_parities(circuit: &CliffordCircuit, n: usize) -> (Vec<Vec<bool>>, Vec<(usize, usize)>) {
+    let mut state: Matrix = (0..n).map(|i| (0..n).map(|j| i == j).collect()).collect();
+    let mut parities = state.clone();
+    let mut moves: Vec<(usize, usize)> = (0..n).map(|q| (0, q)).collect();
+    for (index, gate) in circuit.gates.iter().enumerate() {
+        if let CliffordGate::CNOT(i, j) = gate {
+            rowop(&mut state, *i, *j);
+            parities.push(state[*j].clone());
+            moves.push((index + 1, *j));
+        }
+    }
+    (parities, moves)
+}
+
+/// Synthesizes a unit lower triangular matrix
+fn synthesize_lower_triangular(matrix: &Matrix, niter: usize) -> CliffordCircuit {
+    let n = matrix.len();
+    let mut circuit = CliffordCircuit::new(n);
+    for i in 1..n {
+        let (parities, moves) = gather_parities(&circuit, i);
+        let target = matrix[i][..i].to_vec();
+        let solution = information_set_decoding(&parities, &target, niter.max(1), true)
+            .expect("Something went wrong during syndrome decoding :/");
+        let mut insertions = vec![Vec::new(); circuit.gates.len() + 1];
+        for (index, selected) in solution.iter().enumerate() {
+            if *selected {
+                let (position, qbit) = moves[index];
+                insertions[position].push(CliffordGate::CNOT(qbit, i));
+            }
+        }
+        let mut new_circuit = CliffordCircuit::new(n);
+        new_circuit.gates.extend_from_slice(&insertions[0]);
+        for (gate, inserted) in circuit.gates.iter().zip(insertions.iter().skip(1)) {
+            new_circuit.gates.push(*gate);
+            new_circuit.gates.extend_from_slice(inserted);
+        }
+        circuit = new_circuit;
+    }
+    circuit
+}
+
+/// Synthesizes a CNOT circuit implementing an invertible matrix, using syndrome decoding to
+/// minimize the CNOT count. `niter` is the number of information set decoding attempts.
+pub fn syndrome_synthesis(matrix: &Matrix, niter: usize) -> CliffordCircuit {
+    let n = matrix.len();
+    let (l, u, _, ops) = lu_facto(matrix);
+    // Reversing the qubits turns the upper triangular factor into a lower triangular one
+    let reversal: Vec<usize> = (0..n).rev().collect();
+    let reversed_u: Matrix = reversal
+        .iter()
+        .map(|i| reversal.iter().map(|j| u[*i][*j]).collect())
+        .collect();
+    let mut circuit = synthesize_lower_triangular(&reversed_u, niter).relabel(&reversal);
+    circuit.extend_with(&synthesize_lower_triangular(&l, niter));
+    // `lu_facto` returns the row operations turning the matrix into `LU`, as CNOT(b, a) for the
+    // operation adding row `a` to row `b`
+    for gate in ops.gates.iter().rev() {
+        if let CliffordGate::CNOT(b, a) = gate {
+            circuit.gates.push(CliffordGate::CNOT(*a, *b));
+        }
+    }
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::common::{circuit_to_matrix, random_invertible};
+    use super::*;
+
+    #[test]
+    fn test_syndrome_synthesis() {
+        for _ in 0..10 {
+            let matrix = random_invertible(12);
+            let circuit = syndrome_synthesis(&matrix, 10);
+            assert_eq!(circuit_to_matrix(&circuit), matrix);
+        }
+    }
+
+    #[test]
+    fn test_lower_triangular() {
+        let mut matrix = vec![vec![false; 4]; 4];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for entry in row.iter_mut().take(i + 1) {
+                *entry = true;
+            }
+        }
+        let circuit = synthesize_lower_triangular(&matrix, 10);
+        assert_eq!(circuit_to_matrix(&circuit), matrix);
+        assert_eq!(circuit.gates.len(), 3);
+    }
+}
@@ -0,0 +1,60 @@
+use super::greedy_ge::greedy_ge;
+use super::pmh::pmh_synthesis;
+use super::syndrome::syndrome_synthesis;
+use crate::routines::f2_linalg::{f2_rank, Matrix};
+use crate::structures::{CliffordCircuit, Metric};
+
+/// Synthesizes a CNOT circuit implementing an invertible matrix.
+///
+/// Patel-Markov-Hayes (for all section sizes up to `log2(n)`), greedy Gaussian elimination and
+/// syndrome decoding (with `niter` decoding attempts) are run, and the best circuit according to
+/// `metric` is returned.
+pub fn linear_synthesis(matrix: &Matrix, metric: &Metric, niter: usize) -> CliffordCircuit {
+    let n = matrix.len();
+    if n == 0 {
+        return CliffordCircuit::new(0);
+    }
+    assert!(
+        matrix.iter().all(|row| row.len() == n),
+        "The matrix should be square"
+    );
+    assert_eq!(f2_rank(matrix), n, "The matrix should be invertible");
+    let max_section_size = (usize::BITS - n.leading_zeros()).max(1) as usize;
+    let mut candidates: Vec<CliffordCircuit> = (1..=max_section_size)
+        .map(|section_size| pmh_synthesis(matrix, section_size))
+        .collect();
+    candidates.push(greedy_ge(matrix));
+    candidates.push(syndrome_synthesis(matrix, niter));
+    candidates
+        .into_iter()
+        .min_by_key(|circuit| (metric.on_circuit(circuit), circuit.gates.len()))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::common::{circuit_to_matrix, random_invertible};
+    use super::*;
+
+    #[test]
+    fn test_linear_synthesis() {
+        for metric in [Metric::COUNT, Metric::DEPTH] {
+            for _ in 0..10 {
+                let matrix = random_invertible(10);
+                let circuit = linear_synthesis(&matrix, &metric, 10);
+                assert_eq!(circuit_to_matrix(&circuit), matrix);
+            }
+        }
+    }
+
+    #[test]
+    fn test_permutation() {
+        let mut matrix = vec![vec![false; 3]; 3];
+        for (i, j) in [(0, 1), (1, 2), (2, 0)] {
+            matrix[i][j] = true;
+        }
+        let circuit = linear_synthesis(&matrix, &Metric::COUNT, 10);
+        assert_eq!(circuit_to_matrix(&circuit), matrix);
+        assert_eq!(circuit.cnot_count(), 6);
+    }
+}
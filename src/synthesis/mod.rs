@@ -1,4 +1,7 @@
 //! This module contains all the synthesis methods
 
+pub mod circuit_optimization;
 pub mod clifford;
+pub mod linear;
 pub mod pauli_network;
+pub mod phase_polynomial;
@@ -6,13 +6,24 @@ pub fn pauli_network_synthesis_no_permutation(
     axes: &mut PauliSet,
     metric: &Metric,
     skip_sort: bool,
+) -> CliffordCircuit {
+    pauli_network_synthesis_no_permutation_oriented(axes, metric, skip_sort, &|_, _| true)
+}
+
+/// Same as `pauli_network_synthesis_no_permutation`, preferring CNOT gates `CNOT(i, j)` such
+/// that `native(i, j)` holds
+pub fn pauli_network_synthesis_no_permutation_oriented(
+    axes: &mut PauliSet,
+    metric: &Metric,
+    skip_sort: bool,
+    native: &dyn Fn(usize, usize) -> bool,
 ) -> CliffordCircuit {
     let mut circuit = CliffordCircuit::new(axes.n);
 
     let mut dag = PauliDag::from_pauli_set(axes.clone());
     dag.update_front_nodes();
     while !dag.fully_processed() {
-        dag.single_step_synthesis(metric, skip_sort, &mut circuit);
+        dag.single_step_synthesis_oriented(metric, skip_sort, &mut circuit, native);
     }
     circuit
 }
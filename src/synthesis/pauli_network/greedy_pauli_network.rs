@@ -163,10 +163,29 @@ fn compute_sum_score(pset: &PauliSet, i: usize, j: usize, c: usize, order: &[usi
         + pset.count_leading_i_conjugation(i, j, 1, c, order)
 }
 
+/// Checks if the CNOT gate of a chunk applied on qubits `qbit1` and `qbit2` is native
+fn chunk_is_native(
+    chunk: &Chunk,
+    qbit1: usize,
+    qbit2: usize,
+    native: &dyn Fn(usize, usize) -> bool,
+) -> bool {
+    match chunk[2] {
+        Some(CliffordGate::CNOT(0, _)) => native(qbit1, qbit2),
+        _ => native(qbit2, qbit1),
+    }
+}
+
 /// Finds the Clifford circuit corresponding to the best chunk to apply.
 /// The conjugation of the Pauli set by this circuit is done in the main algorithm.
-fn single_synthesis_step_count(pset: &PauliSet, order: &[usize]) -> CliffordCircuit {
+/// Ties are broken in favor of chunks whose CNOT gate is native.
+fn single_synthesis_step_count(
+    pset: &PauliSet,
+    order: &[usize],
+    native: &dyn Fn(usize, usize) -> bool,
+) -> CliffordCircuit {
     let mut max_score = -1;
+    let mut best_native = false;
     let mut best_i = 0;
     let mut best_j = 0;
     let mut best_c: usize = 0;
@@ -174,10 +193,12 @@ fn single_synthesis_step_count(pset: &PauliSet, order: &[usize]) -> CliffordCirc
     let support = pset.get_support(order[0]);
     for i in 0..support.len() {
         for j in 0..i {
-            for c in 0..18 {
+            for (c, chunk) in ALL_CHUNKS.iter().enumerate() {
                 let score = compute_max_score(pset, support[i], support[j], c, order) as i32;
-                if score > max_score {
+                let is_native = chunk_is_native(chunk, support[i], support[j], native);
+                if score > max_score || (score == max_score && is_native && !best_native) {
                     max_score = score;
+                    best_native = is_native;
                     best_c = c;
                     best_i = i;
                     best_j = j;
@@ -197,6 +218,7 @@ fn single_synthesis_step_count(pset: &PauliSet, order: &[usize]) -> CliffordCirc
 fn build_graph(
     bucket: &PauliSet,
     order: &[usize],
+    native: &dyn Fn(usize, usize) -> bool,
 ) -> (UnGraph<(), i32>, HashMap<(usize, usize), Chunk>) {
     let mut graph: UnGraph<(), i32> = UnGraph::new_undirected();
     let mut best_chunks: HashMap<(usize, usize), Chunk> = HashMap::new();
@@ -209,11 +231,16 @@ fn build_graph(
             let init_count = (bucket.count_leading_i(qbit1, order)
                 + bucket.count_leading_i(qbit2, order)) as i32;
             let mut max_score = 0;
+            let mut best_native = false;
             let mut best_chunk: Chunk = [None; 3];
             for (c, _) in ALL_CHUNKS.iter().enumerate() {
                 let score = compute_sum_score(bucket, qbit1, qbit2, c, order) as i32 - init_count;
-                if score > max_score {
+                let is_native = chunk_is_native(&ALL_CHUNKS[c], qbit1, qbit2, native);
+                if score > max_score
+                    || (score > 0 && score == max_score && is_native && !best_native)
+                {
                     max_score = score;
+                    best_native = is_native;
                     best_chunk = ALL_CHUNKS[c];
                 }
                 best_chunks.insert((qbit1, qbit2), best_chunk);
@@ -227,8 +254,12 @@ fn build_graph(
     (graph, best_chunks)
 }
 
-fn single_synthesis_step_depth(bucket: &PauliSet, order: &[usize]) -> CliffordCircuit {
-    let (graph, best_chunks) = build_graph(bucket, order);
+fn single_synthesis_step_depth(
+    bucket: &PauliSet,
+    order: &[usize],
+    native: &dyn Fn(usize, usize) -> bool,
+) -> CliffordCircuit {
+    let (graph, best_chunks) = build_graph(bucket, order, native);
     let matching = maximum_matching(&graph);
     let mut circuit_piece = CliffordCircuit::new(bucket.n);
     for (qbit1, qbit2) in matching.edges() {
@@ -248,10 +279,21 @@ pub fn single_synthesis_step(
     bucket: &PauliSet,
     metric: &Metric,
     order: &[usize],
+) -> CliffordCircuit {
+    single_synthesis_step_oriented(bucket, metric, order, &|_, _| true)
+}
+
+/// Same as `single_synthesis_step`, but prefers chunks whose CNOT gate `CNOT(i, j)` satisfies
+/// `native(i, j)` among chunks with the same score.
+pub fn single_synthesis_step_oriented(
+    bucket: &PauliSet,
+    metric: &Metric,
+    order: &[usize],
+    native: &dyn Fn(usize, usize) -> bool,
 ) -> CliffordCircuit {
     match metric {
-        Metric::COUNT => single_synthesis_step_count(bucket, order),
-        Metric::DEPTH => single_synthesis_step_depth(bucket, order),
+        Metric::COUNT => single_synthesis_step_count(bucket, order, native),
+        Metric::DEPTH => single_synthesis_step_depth(bucket, order, native),
     }
 }
 
@@ -259,6 +301,17 @@ pub fn pauli_network_synthesis(
     bucket: &mut PauliSet,
     metric: &Metric,
     skip_sort: bool,
+) -> CliffordCircuit {
+    pauli_network_synthesis_oriented(bucket, metric, skip_sort, &|_, _| true)
+}
+
+/// Same as `pauli_network_synthesis`, preferring native CNOT orientations (see
+/// `single_synthesis_step_oriented`).
+pub fn pauli_network_synthesis_oriented(
+    bucket: &mut PauliSet,
+    metric: &Metric,
+    skip_sort: bool,
+    native: &dyn Fn(usize, usize) -> bool,
 ) -> CliffordCircuit {
     if bucket.is_empty() {
         return CliffordCircuit::new(0);
@@ -278,7 +331,7 @@ pub fn pauli_network_synthesis(
             break;
         }
         let order: Vec<usize> = (0..bucket.len()).collect();
-        let circuit_piece = single_synthesis_step(bucket, metric, &order);
+        let circuit_piece = single_synthesis_step_oriented(bucket, metric, &order, native);
         output.extend_with(&circuit_piece);
         bucket.conjugate_with_circuit(&circuit_piece);
     }
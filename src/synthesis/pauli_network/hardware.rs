@@ -0,0 +1,256 @@
+//! Architecture-aware Pauli network synthesis.
+//!
+//! Each rotation is synthesized by folding its support onto a single qubit along a Steiner tree
+//! of the hardware graph. All CNOT gates of the resulting network act on coupled qubits.
+use crate::routines::tree_folding::{fold_along_tree, FoldBasis};
+use crate::structures::{CliffordCircuit, HardwareGraph, PauliDag, PauliLike, PauliSet};
+
+/// A Steiner tree of the support of an operator, along with its cost
+#[derive(Debug, Clone)]
+struct SupportTree {
+    support: Vec<usize>,
+    edges: Vec<(usize, usize)>,
+    cost: f64,
+}
+
+impl SupportTree {
+    fn new(support: Vec<usize>, hardware: &HardwareGraph) -> Self {
+        let tree = hardware.get_steiner_tree(&support);
+        Self {
+            cost: hardware.steiner_cost(&tree),
+            edges: tree.edges(),
+            support,
+        }
+    }
+}
+
+/// The Steiner trees of the operators of a bucket.
+///
+/// A tree is only recomputed when the support of its operator changes.
+#[derive(Debug, Clone)]
+struct TreeCache {
+    trees: Vec<Option<SupportTree>>,
+}
+
+impl TreeCache {
+    fn new(size: usize) -> Self {
+        Self {
+            trees: vec![None; size],
+        }
+    }
+    fn get(&mut self, bucket: &PauliSet, index: usize, hardware: &HardwareGraph) -> &SupportTree {
+        let support = bucket.get_support(index);
+        let entry = &mut self.trees[index];
+        if entry.as_ref().map(|tree| &tree.support) != Some(&support) {
+            *entry = Some(SupportTree::new(support, hardware));
+        }
+        entry.as_ref().unwrap()
+    }
+}
+
+/// Computes the circuit folding operator `index` onto a single qubit along `tree`.
+///
+/// All the terminals of the tree are tried as roots, and the one minimizing the total support
+/// of the `pending` operators is kept.
+fn reduce_operator(
+    bucket: &PauliSet,
+    index: usize,
+    tree: &SupportTree,
+    pending: &[usize],
+) -> CliffordCircuit {
+    let mut best_piece = CliffordCircuit::new(bucket.n);
+    let mut best_score = usize::MAX;
+    for root in tree.support.iter() {
+        let piece = fold_along_tree(bucket, index, &tree.edges, *root, FoldBasis::Z);
+        let mut copy = bucket.clone();
+        copy.conjugate_with_circuit(&piece);
+        let score: usize = pending.iter().map(|i| copy.support_size(*i)).sum();
+        if score < best_score {
+            best_score = score;
+            best_piece = piece;
+        }
+    }
+    best_piece
+}
+
+/// Picks the operator with the cheapest Steiner tree among `candidates`
+fn pick_cheapest(
+    bucket: &PauliSet,
+    candidates: &[usize],
+    hardware: &HardwareGraph,
+    trees: &mut TreeCache,
+) -> usize {
+    candidates
+        .iter()
+        .map(|i| {
+            let tree = trees.get(bucket, *i, hardware);
+            (*i, tree.cost, tree.support.len())
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1).then(a.2.cmp(&b.2)))
+        .unwrap()
+        .0
+}
+
+fn pauli_network_synthesis_hardware(
+    bucket: &PauliSet,
+    hardware: &HardwareGraph,
+) -> CliffordCircuit {
+    let mut bucket = bucket.clone();
+    let mut circuit = CliffordCircuit::new(bucket.n);
+    let mut pending: Vec<usize> = (0..bucket.len())
+        .filter(|i| bucket.support_size(*i) > 1)
+        .collect();
+    let mut trees = TreeCache::new(bucket.len());
+    while !pending.is_empty() {
+        let index = pick_cheapest(&bucket, &pending, hardware, &mut trees);
+        let tree = trees.get(&bucket, index, hardware);
+        let piece = reduce_operator(&bucket, index, tree, &pending);
+        bucket.conjugate_with_circuit(&piece);
+        circuit.extend_with(&piece);
+        pending.retain(|i| bucket.support_size(*i) > 1);
+    }
+    circuit
+}
+
+fn pauli_network_synthesis_hardware_no_permutation(
+    bucket: &PauliSet,
+    hardware: &HardwareGraph,
+) -> CliffordCircuit {
+    let mut circuit = CliffordCircuit::new(bucket.n);
+    let mut dag = PauliDag::from_pauli_set(bucket.clone());
+    dag.update_front_nodes();
+    let mut trees = TreeCache::new(dag.pauli_set.len());
+    while !dag.fully_processed() {
+        let front: Vec<usize> = dag.front_nodes.iter().map(|k| k.index()).collect();
+        let index = pick_cheapest(&dag.pauli_set, &front, hardware, &mut trees);
+        let tree = trees.get(&dag.pauli_set, index, hardware);
+        let piece = reduce_operator(&dag.pauli_set, index, tree, &front);
+        dag.pauli_set.conjugate_with_circuit(&piece);
+        circuit.extend_with(&piece);
+        dag.update_front_nodes();
+    }
+    circuit
+}
+
+/// Synthesizes a Pauli network for a sequence of rotations on a given hardware.
+///
+/// All the CNOT gates of the output circuit act on pairs of qubits coupled in `hardware`.
+/// The qubits of the operators are identified with the qubits of the hardware graph, which
+/// is assumed to be connected.
+///
+/// If `preserve_order` is set, the rotations are synthesized following their anti-commutation
+/// DAG. Otherwise, the cheapest remaining rotation is synthesized at each step.
+pub fn pauli_network_on_hardware(
+    operator_sequence: &PauliSet,
+    hardware: &HardwareGraph,
+    preserve_order: bool,
+) -> CliffordCircuit {
+    assert_eq!(
+        operator_sequence.n,
+        hardware.len(),
+        "The hardware graph and the operators should have the same number of qubits"
+    );
+    if preserve_order {
+        pauli_network_synthesis_hardware_no_permutation(operator_sequence, hardware)
+    } else {
+        pauli_network_synthesis_hardware(operator_sequence, hardware)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::CliffordGate;
+    use crate::synthesis::pauli_network::check_circuit;
+    use rand::Rng;
+
+    fn random_instance(n: usize, m: usize) -> PauliSet {
+        let mut rng = rand::thread_rng();
+        let mut pset = PauliSet::new(n);
+        for _ in 0..m {
+            let vec: Vec<bool> = (0..2 * n).map(|_| rng.gen::<bool>()).collect();
+            pset.insert_vec_bool(&vec, false);
+        }
+        pset
+    }
+
+    fn check_hardware_circuit(
+        operator_sequence: &PauliSet,
+        hardware: &HardwareGraph,
+        circuit: &CliffordCircuit,
+    ) {
+        for gate in circuit.gates.iter() {
+            if let CliffordGate::CNOT(i, j) = gate {
+                assert!(hardware.are_connected(*i, *j));
+            }
+        }
+        let input: Vec<String> = (0..operator_sequence.len())
+            .map(|i| operator_sequence.get(i).1)
+            .collect();
+        check_circuit(&input, circuit);
+    }
+
+    #[test]
+    fn test_line() {
+        let hardware = HardwareGraph::line(6);
+        for preserve_order in [true, false] {
+            let instance = random_instance(6, 30);
+            let circuit = pauli_network_on_hardware(&instance, &hardware, preserve_order);
+            check_hardware_circuit(&instance, &hardware, &circuit);
+        }
+    }
+
+    #[test]
+    fn test_grid() {
+        let hardware = HardwareGraph::grid(3, 3);
+        for preserve_order in [true, false] {
+            let instance = random_instance(9, 50);
+            let circuit = pauli_network_on_hardware(&instance, &hardware, preserve_order);
+            check_hardware_circuit(&instance, &hardware, &circuit);
+        }
+    }
+
+    #[test]
+    fn test_avoid_expensive_coupling() {
+        let hardware = HardwareGraph::from_weighted_couplings(&[
+            (0, 1, 1.),
+            (1, 2, 1.),
+            (2, 3, 1.),
+            (3, 0, 10.),
+        ]);
+        let instance = PauliSet::from_slice(&["XIIX".to_string(), "ZIIZ".to_string()]);
+        let circuit = pauli_network_on_hardware(&instance, &hardware, true);
+        check_hardware_circuit(&instance, &hardware, &circuit);
+        for gate in circuit.gates.iter() {
+            assert_ne!(*gate, CliffordGate::CNOT(0, 3));
+            assert_ne!(*gate, CliffordGate::CNOT(3, 0));
+        }
+    }
+
+    #[test]
+    fn test_tree_cache() {
+        let hardware = HardwareGraph::line(4);
+        let mut bucket = PauliSet::from_slice(&["XIIX".to_string(), "IZZI".to_string()]);
+        let mut trees = TreeCache::new(bucket.len());
+        assert_eq!(trees.get(&bucket, 0, &hardware).edges.len(), 3);
+        assert_eq!(trees.get(&bucket, 1, &hardware).cost, 1.);
+        let mut circuit = CliffordCircuit::new(4);
+        circuit.gates.push(CliffordGate::CNOT(0, 3));
+        bucket.conjugate_with_circuit(&circuit);
+        // The support of the first operator shrank to a single qubit
+        let tree = trees.get(&bucket, 0, &hardware);
+        assert_eq!(tree.support, vec![0]);
+        assert!(tree.edges.is_empty());
+        assert_eq!(trees.get(&bucket, 1, &hardware).support, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_heavy_hex() {
+        let hardware = HardwareGraph::heavy_hex(1, 1);
+        for preserve_order in [true, false] {
+            let instance = random_instance(12, 30);
+            let circuit = pauli_network_on_hardware(&instance, &hardware, preserve_order);
+            check_hardware_circuit(&instance, &hardware, &circuit);
+        }
+    }
+}
@@ -32,9 +32,46 @@
 //! // Same thing, performing 10 attempts and returning the best
 //! let circuit = greedy_pauli_network(&mut paulis, &Metric::DEPTH, true, 10, false, true);
 //! ```
+//!
+//! # Hardware-aware synthesis
+//!
+//! Method [pauli_network_on_hardware] produces a network whose CNOT gates only act on
+//! pairs of qubits coupled in a given `HardwareGraph`.
+//! ```
+//! use rustiq_core::structures::{HardwareGraph, PauliSet};
+//! use rustiq_core::synthesis::pauli_network::pauli_network_on_hardware;
+//! let paulis = PauliSet::from_slice(&["XIX".to_string(), "ZZZ".to_string(), "YIY".to_string()]);
+//! // A line 0 - 1 - 2
+//! let hardware = HardwareGraph::from_couplings(&[(0, 1), (1, 2)]);
+//! // preserving the rotation order
+//! let circuit = pauli_network_on_hardware(&paulis, &hardware, true);
+//! ```
+//!
+//! When couplings only support CNOT gates in one orientation, the output of any synthesis method
+//! can be fixed using [fix_cnot_orientation](crate::routines::orientation::fix_cnot_orientation).
+//! Method [greedy_pauli_network_oriented] also favors native orientations during synthesis.
+//!
+//! # Inserting the rotations
+//!
+//! Method [locate_rotations] computes where each rotation lands in a network, and
+//! [pauli_network_to_circuit] directly produces a [Circuit](crate::structures::Circuit)
+//! interleaving the network with the corresponding RZ gates.
+//! ```
+//! use rustiq_core::structures::{Metric, Parameter, PauliSet};
+//! use rustiq_core::synthesis::pauli_network::{greedy_pauli_network, pauli_network_to_circuit};
+//! let paulis = PauliSet::from_slice(&["XX".to_string(), "ZZ".to_string(), "YY".to_string()]);
+//! let network = greedy_pauli_network(&paulis, &Metric::COUNT, true, 0, false, false);
+//! let angles = vec![Parameter::Abstract("theta".to_string()); 3];
+//! let circuit = pauli_network_to_circuit(&paulis, &angles, &network, true).unwrap();
+//! assert_eq!(circuit.rz_count(), 3);
+//! ```
 pub mod chunks;
 pub mod greedy_order_preserving;
 pub mod greedy_pauli_network;
+pub mod hardware;
+pub mod rotations;
 pub mod synthesis;
 
-pub use synthesis::{check_circuit, greedy_pauli_network};
+pub use hardware::pauli_network_on_hardware;
+pub use rotations::{locate_rotations, pauli_network_to_circuit, RotationLocation};
+pub use synthesis::{check_circuit, greedy_pauli_network, greedy_pauli_network_oriented};
@@ -0,0 +1,245 @@
+//! Helpers locating the rotations implemented by a Pauli network.
+use crate::structures::{
+    Circuit, CliffordCircuit, CliffordGate, Gate, Parameter, PauliLike, PauliSet,
+};
+
+/// Describes where a rotation lands in a Pauli network
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RotationLocation {
+    /// Number of gates of the network applied before the rotation
+    pub position: usize,
+    /// The qubit carrying the rotation
+    pub qbit: usize,
+    /// The single qubit Pauli operator the rotation axis is mapped to (`'I'` for identity operators)
+    pub pauli: char,
+    /// True if the rotation axis is mapped to minus `pauli`
+    pub sign: bool,
+}
+
+/// Computes where each operator of `operators` can be implemented along the Pauli network
+/// `circuit`, i.e. the first point of the network where its image has a support of size 1.
+///
+/// If `preserve_order` is true, an operator is only located after all the preceding operators
+/// it does not commute with. Identity operators are located at position 0 with a `'I'` Pauli.
+///
+/// Returns an error if some operator is never mapped to a single qubit operator.
+pub fn locate_rotations(
+    operators: &PauliSet,
+    circuit: &CliffordCircuit,
+    preserve_order: bool,
+) -> Result<Vec<RotationLocation>, String> {
+    let mut bucket = operators.clone();
+    let mut locations: Vec<Option<RotationLocation>> = vec![None; bucket.len()];
+    for (i, location) in locations.iter_mut().enumerate() {
+        if bucket.support_size(i) == 0 {
+            *location = Some(RotationLocation {
+                position: 0,
+                qbit: 0,
+                pauli: 'I',
+                sign: bucket.get_phase(i),
+            });
+        }
+    }
+    for position in 0..=circuit.gates.len() {
+        if position > 0 {
+            bucket.conjugate_with_gate(&circuit.gates[position - 1]);
+        }
+        for i in 0..bucket.len() {
+            if locations[i].is_some() || bucket.support_size(i) != 1 {
+                continue;
+            }
+            if preserve_order && !(0..i).all(|j| locations[j].is_some() || bucket.commute(i, j)) {
+                continue;
+            }
+            let qbit = bucket.get_support(i)[0];
+            let (sign, pstring) = bucket.get(i);
+            locations[i] = Some(RotationLocation {
+                position,
+                qbit,
+                pauli: pstring.chars().nth(qbit).unwrap(),
+                sign,
+            });
+        }
+    }
+    locations
+        .into_iter()
+        .enumerate()
+        .map(|(i, location)| {
+            location.ok_or(format!(
+                "Operator {} is never mapped to a single qubit operator",
+                operators.get(i).1
+            ))
+        })
+        .collect()
+}
+
+/// Builds the circuit implementing the rotations `exp(-i angles[k] operators[k] / 2)` by inserting
+/// RZ gates (surrounded by basis changes for X and Y rotations) inside the Pauli network `circuit`.
+///
+/// The output circuit implements the sequence of rotations followed by the Clifford operator of
+/// the network. See [locate_rotations] for the meaning of `preserve_order`.
+pub fn pauli_network_to_circuit(
+    operators: &PauliSet,
+    angles: &[Parameter],
+    circuit: &CliffordCircuit,
+    preserve_order: bool,
+) -> Result<Circuit, String> {
+    if angles.len() != operators.len() {
+        return Err(format!(
+            "Expected {} angles, got {}",
+            operators.len(),
+            angles.len()
+        ));
+    }
+    let locations = locate_rotations(operators, circuit, preserve_order)?;
+    let mut insertions: Vec<Vec<usize>> = vec![Vec::new(); circuit.gates.len() + 1];
+    for (i, location) in locations.iter().enumerate() {
+        if location.pauli != 'I' {
+            insertions[location.position].push(i);
+        }
+    }
+    let mut output = Circuit::new(circuit.nqbits.max(operators.n));
+    for (position, indices) in insertions.iter().enumerate() {
+        for i in indices {
+            let location = &locations[*i];
+            let qbit = location.qbit;
+            let mut angle = angles[*i].clone();
+            if location.sign {
+                angle.flip_sign();
+            }
+            let (before, after) = match location.pauli {
+                'X' => (Some(CliffordGate::H(qbit)), Some(CliffordGate::H(qbit))),
+                'Y' => (
+                    Some(CliffordGate::SqrtX(qbit)),
+                    Some(CliffordGate::SqrtXd(qbit)),
+                ),
+                _ => (None, None),
+            };
+            output.gates.extend(before.map(Gate::Clifford));
+            output.gates.push(Gate::Rz(qbit, angle));
+            output.gates.extend(after.map(Gate::Clifford));
+        }
+        if position < circuit.gates.len() {
+            output.gates.push(Gate::Clifford(circuit.gates[position]));
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::{Metric, Tableau};
+    use crate::synthesis::pauli_network::greedy_pauli_network;
+    use rand::Rng;
+
+    fn random_operators(n: usize, k: usize) -> Vec<String> {
+        let mut rng = rand::thread_rng();
+        let mut operators: Vec<String> = Vec::new();
+        while operators.len() < k {
+            let op: String = (0..n)
+                .map(|_| ['I', 'X', 'Y', 'Z'][rng.gen_range(0..4)])
+                .collect();
+            if op.chars().any(|c| c != 'I') && !operators.contains(&op) {
+                operators.push(op);
+            }
+        }
+        operators
+    }
+
+    /// Extracts the (axis, angle) sequence implemented by a circuit
+    fn extract(circuit: &Circuit) -> (Vec<(String, f64)>, Tableau) {
+        let (rotations, clifford) = circuit.to_rotations();
+        let rotations = rotations
+            .into_iter()
+            .map(|(axis, angle)| match angle {
+                Parameter::Concrete(x) => (axis, x),
+                _ => panic!("Expected a concrete angle"),
+            })
+            .collect();
+        (rotations, clifford)
+    }
+
+    #[test]
+    fn test_pauli_network_to_circuit() {
+        let n = 5;
+        for preserve_order in [true, false] {
+            for _ in 0..10 {
+                let mut operators = random_operators(n, 20);
+                if !preserve_order {
+                    // Keep a commuting subset so that the order can be relaxed
+                    let pset = PauliSet::from_slice(&operators);
+                    let mut kept: Vec<usize> = Vec::new();
+                    for i in 0..pset.len() {
+                        if kept.iter().all(|j| pset.commute(i, *j)) {
+                            kept.push(i);
+                        }
+                    }
+                    operators = kept.into_iter().map(|i| operators[i].clone()).collect();
+                }
+                let pset = PauliSet::from_slice(&operators);
+                let angles: Vec<Parameter> = (0..operators.len())
+                    .map(|i| Parameter::Concrete(0.1 * (i + 1) as f64))
+                    .collect();
+                let network =
+                    greedy_pauli_network(&pset, &Metric::COUNT, preserve_order, 0, false, false);
+                let circuit =
+                    pauli_network_to_circuit(&pset, &angles, &network, preserve_order).unwrap();
+                assert_eq!(circuit.rz_count(), operators.len());
+                let (mut rotations, clifford) = extract(&circuit);
+                assert_eq!(clifford, Tableau::from_circuit(&network));
+                // Non commuting rotations are implemented in order
+                let order: Vec<usize> = rotations
+                    .iter()
+                    .map(|(axis, _)| operators.iter().position(|op| op == axis).unwrap())
+                    .collect();
+                for a in 0..order.len() {
+                    for b in a + 1..order.len() {
+                        assert!(order[a] < order[b] || pset.commute(order[a], order[b]));
+                    }
+                }
+                rotations.sort_by(|a, b| a.0.cmp(&b.0));
+                let mut expected: Vec<(String, f64)> = operators
+                    .iter()
+                    .cloned()
+                    .zip(angles.iter().map(|angle| match angle {
+                        Parameter::Concrete(x) => *x,
+                        _ => unreachable!(),
+                    }))
+                    .collect();
+                expected.sort_by(|a, b| a.0.cmp(&b.0));
+                for ((axis, angle), (expected_axis, expected_angle)) in
+                    rotations.iter().zip(expected.iter())
+                {
+                    assert_eq!(axis, expected_axis);
+                    assert!((angle - expected_angle).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_locate_rotations() {
+        let pset = PauliSet::from_slice(&["ZZ".to_owned(), "IX".to_owned(), "II".to_owned()]);
+        let network = CliffordCircuit::from_vec(vec![("CNOT".to_owned(), vec![0, 1])]);
+        let locations = locate_rotations(&pset, &network, true).unwrap();
+        // ZZ lands on qubit 1 after the CNOT, IX can only be implemented after ZZ
+        assert_eq!(
+            locations[0],
+            RotationLocation {
+                position: 1,
+                qbit: 1,
+                pauli: 'Z',
+                sign: false
+            }
+        );
+        assert_eq!(locations[1].position, 1);
+        assert_eq!(locations[1].pauli, 'X');
+        assert_eq!(locations[2].pauli, 'I');
+        // Without order preservation, IX is implemented right away
+        let locations = locate_rotations(&pset, &network, false).unwrap();
+        assert_eq!(locations[1].position, 0);
+        // The empty network does not implement ZZ
+        assert!(locate_rotations(&pset, &CliffordCircuit::new(2), true).is_err());
+    }
+}
@@ -1,9 +1,10 @@
 use std::collections::HashSet;
 
-use super::greedy_order_preserving::pauli_network_synthesis_no_permutation;
-use super::greedy_pauli_network::pauli_network_synthesis;
+use super::greedy_order_preserving::pauli_network_synthesis_no_permutation_oriented;
+use super::greedy_pauli_network::pauli_network_synthesis_oriented;
+use crate::routines::orientation::fix_cnot_orientation;
 use crate::structures::{
-    CliffordCircuit, CliffordGate, IsometryTableau, Metric, PauliLike, PauliSet,
+    CliffordCircuit, CliffordGate, HardwareGraph, IsometryTableau, Metric, PauliLike, PauliSet,
 };
 use crate::synthesis::clifford::isometry::isometry_synthesis;
 use rand::thread_rng;
@@ -76,18 +77,72 @@ pub fn greedy_pauli_network(
     nshuffles: usize,
     skip_sort: bool,
     fix_clifford: bool,
+) -> CliffordCircuit {
+    greedy_pauli_network_with_orientation(
+        operator_sequence,
+        metric,
+        preserve_order,
+        nshuffles,
+        skip_sort,
+        fix_clifford,
+        &|_, _| true,
+    )
+}
+
+/// Same as [greedy_pauli_network], targeting a hardware whose couplings only support CNOT gates
+/// in some orientations.
+///
+/// When scoring the Pauli network chunks, ties are broken in favor of native CNOT orientations.
+/// The CNOT gates of the output circuit that are still not native are then reoriented using
+/// [fix_cnot_orientation]. Note that the connectivity of `hardware` is not enforced: the output
+/// circuit might contain CNOT gates acting on uncoupled qubits.
+pub fn greedy_pauli_network_oriented(
+    operator_sequence: &PauliSet,
+    hardware: &HardwareGraph,
+    metric: &Metric,
+    preserve_order: bool,
+    nshuffles: usize,
+    skip_sort: bool,
+    fix_clifford: bool,
+) -> CliffordCircuit {
+    assert_eq!(
+        operator_sequence.n,
+        hardware.len(),
+        "The hardware graph and the operators should have the same number of qubits"
+    );
+    let circuit = greedy_pauli_network_with_orientation(
+        operator_sequence,
+        metric,
+        preserve_order,
+        nshuffles,
+        skip_sort,
+        fix_clifford,
+        &|i, j| hardware.is_native(i, j),
+    );
+    fix_cnot_orientation(&circuit, hardware)
+}
+
+fn greedy_pauli_network_with_orientation(
+    operator_sequence: &PauliSet,
+    metric: &Metric,
+    preserve_order: bool,
+    nshuffles: usize,
+    skip_sort: bool,
+    fix_clifford: bool,
+    native: &dyn Fn(usize, usize) -> bool,
 ) -> CliffordCircuit {
     let synth = if preserve_order {
-        pauli_network_synthesis_no_permutation
+        pauli_network_synthesis_no_permutation_oriented
     } else {
-        pauli_network_synthesis
+        pauli_network_synthesis_oriented
     };
-    let mut circuit = synth(&mut operator_sequence.clone(), metric, skip_sort);
+    let mut circuit = synth(&mut operator_sequence.clone(), metric, skip_sort, native);
     let mut cost = metric.on_circuit(&circuit);
     for _ in 0..nshuffles {
         let mut pset = operator_sequence.clone();
         let permutation = permute_input(&mut pset);
-        let new_circuit = synth(&mut pset, metric, skip_sort);
+        let permuted_native = |i: usize, j: usize| native(permutation[i], permutation[j]);
+        let new_circuit = synth(&mut pset, metric, skip_sort, &permuted_native);
         let new_cost = metric.on_circuit(&new_circuit);
         if new_cost < cost {
             cost = new_cost;
@@ -106,6 +161,8 @@ pub fn greedy_pauli_network(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
     #[test]
     fn test_inifinte_loop_identities() {
         let mut operator_sequence = PauliSet::new(4);
@@ -217,4 +274,100 @@ mod tests {
             &result,
         );
     }
+
+    #[test]
+    fn test_native_orientation() {
+        let operators = [
+            "XZYX".to_owned(),
+            "XXIY".to_owned(),
+            "ZZYI".to_owned(),
+            "XZZZ".to_owned(),
+            "ZYZY".to_owned(),
+        ];
+        let operator_sequence = PauliSet::from_slice(&operators);
+        // All qubits are coupled, but CNOT gates can only target higher qubits
+        let mut couplings = Vec::new();
+        for i in 0..4 {
+            for j in i + 1..4 {
+                couplings.push((i, j));
+            }
+        }
+        let hardware = HardwareGraph::from_directed_couplings(&couplings);
+        for metric in [Metric::COUNT, Metric::DEPTH] {
+            for preserve_order in [true, false] {
+                let result = greedy_pauli_network_oriented(
+                    &operator_sequence,
+                    &hardware,
+                    &metric,
+                    preserve_order,
+                    3,
+                    false,
+                    true,
+                );
+                for gate in result.gates.iter() {
+                    if let CliffordGate::CNOT(i, j) = gate {
+                        assert!(i < j);
+                    }
+                }
+                check_circuit(&operators, &result);
+            }
+        }
+    }
+
+    #[test]
+    fn test_native_tie_breaking() {
+        // CNOT gates can only target higher qubits
+        let native = |i: usize, j: usize| i < j;
+        let non_native = |circuit: &CliffordCircuit| {
+            circuit
+                .gates
+                .iter()
+                .filter(|gate| matches!(gate, CliffordGate::CNOT(i, j) if !native(*i, *j)))
+                .count()
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        for metric in [Metric::COUNT, Metric::DEPTH] {
+            for preserve_order in [true, false] {
+                let (mut oriented_total, mut unoriented_total) = (0, 0);
+                for _ in 0..20 {
+                    let operators: Vec<String> = (0..10)
+                        .map(|_| {
+                            (0..5)
+                                .map(|_| ['I', 'X', 'Y', 'Z'][rng.gen_range(0..4)])
+                                .collect()
+                        })
+                        .collect();
+                    let operator_sequence = PauliSet::from_slice(&operators);
+                    // The orientation fixing pass is disabled: only the scoring of the chunks
+                    // accounts for the native orientations
+                    let oriented = greedy_pauli_network_with_orientation(
+                        &operator_sequence,
+                        &metric,
+                        preserve_order,
+                        0,
+                        false,
+                        false,
+                        &native,
+                    );
+                    let unoriented = greedy_pauli_network_with_orientation(
+                        &operator_sequence,
+                        &metric,
+                        preserve_order,
+                        0,
+                        false,
+                        false,
+                        &|_, _| true,
+                    );
+                    check_circuit(&operators, &oriented);
+                    oriented_total += non_native(&oriented);
+                    unoriented_total += non_native(&unoriented);
+                }
+                println!(
+                    "{:?} {} {} {}",
+                    metric, preserve_order, oriented_total, unoriented_total
+                );
+                assert!(oriented_total < unoriented_total);
+            }
+        }
+    }
 }
@@ -0,0 +1,86 @@
+//! Bookkeeping shared by the phase polynomial synthesis algorithms.
+use super::synthesis::RzGates;
+use crate::routines::f2_linalg::{inverse_f2, rowop, Matrix};
+use crate::structures::{CliffordCircuit, CliffordGate, Metric, Parameter};
+use crate::synthesis::linear::common::identity;
+use crate::synthesis::linear::linear_synthesis;
+
+/// A CNOT circuit under construction, together with the parities that remain to be computed.
+///
+/// Each parity is stored in the basis of the current wires: parity `p` is carried by wire `q`
+/// when `parities[p]` is the unit vector `e_q`. Applying `CNOT(c, t)` adds coordinate `t` to
+/// coordinate `c` of every parity.
+#[derive(Clone)]
+pub(crate) struct ParityNetwork {
+    pub n: usize,
+    pub circuit: CliffordCircuit,
+    pub parities: Vec<Vec<bool>>,
+    pub placed: Vec<bool>,
+    /// The parity carried by each wire, as a combination of the input qubits
+    wires: Matrix,
+    angles: Vec<Parameter>,
+    rotations: RzGates,
+}
+
+impl ParityNetwork {
+    pub fn new(n: usize, parities: Vec<Vec<bool>>, angles: Vec<Parameter>) -> Self {
+        let mut network = Self {
+            n,
+            circuit: CliffordCircuit::new(n),
+            placed: vec![false; parities.len()],
+            parities,
+            wires: identity(n),
+            angles,
+            rotations: Vec::new(),
+        };
+        network.place_rotations();
+        network
+    }
+
+    /// Indices of the parities that are not yet carried by any wire
+    pub fn remaining(&self) -> Vec<usize> {
+        (0..self.parities.len())
+            .filter(|p| !self.placed[*p])
+            .collect()
+    }
+
+    /// Places the rotations whose parity is carried by a wire
+    fn place_rotations(&mut self) {
+        for p in 0..self.parities.len() {
+            if self.placed[p] {
+                continue;
+            }
+            let mut support = (0..self.n).filter(|q| self.parities[p][*q]);
+            if let (Some(qbit), None) = (support.next(), support.next()) {
+                self.rotations
+                    .push((self.circuit.gates.len(), qbit, self.angles[p].clone()));
+                self.placed[p] = true;
+            }
+        }
+    }
+
+    pub fn cnot(&mut self, control: usize, target: usize) {
+        self.circuit.gates.push(CliffordGate::CNOT(control, target));
+        rowop(&mut self.wires, control, target);
+        for parity in self.parities.iter_mut() {
+            parity[control] ^= parity[target];
+        }
+        self.place_rotations();
+    }
+
+    /// Computes the parity `p` on one of the qubits of its support
+    pub fn place_directly(&mut self, p: usize) {
+        let support: Vec<usize> = (0..self.n).filter(|q| self.parities[p][*q]).collect();
+        for control in support.iter().skip(1) {
+            self.cnot(*control, support[0]);
+        }
+    }
+
+    /// Restores the identity linear map and returns the circuit and the rotations
+    pub fn finish(mut self, metric: &Metric, niter: usize) -> (CliffordCircuit, RzGates) {
+        assert!(self.placed.iter().all(|b| *b));
+        let restoration = linear_synthesis(&inverse_f2(&self.wires), metric, niter);
+        self.circuit.extend_with(&restoration);
+        (self.circuit, self.rotations)
+    }
+}
@@ -0,0 +1,60 @@
+//! The Gray-synth algorithm, targeting CNOT count.
+//!
+//! Reference: M. Amy, P. Azimzadeh, M. Mosca, "On the CNOT-complexity of CNOT-phase circuits",
+//! Quantum Science and Technology 4(1), 2018.
+//!
+//! The parities are recursively split according to the value of their coordinate on some qubit,
+//! chosen to keep the largest possible group together. Within a group whose parities all contain
+//! some target qubit, any other qubit shared by all the parities is merged into the target with a
+//! single CNOT gate.
+use super::common::ParityNetwork;
+
+/// Runs one pass of Gray-synth over the parities that are not yet placed
+fn gray_synth_pass(network: &mut ParityNetwork) {
+    let n = network.n;
+    let mut stack: Vec<(Vec<usize>, Vec<usize>, Option<usize>)> =
+        vec![(network.remaining(), (0..n).collect(), None)];
+    while let Some((mut set, qubits, target)) = stack.pop() {
+        set.retain(|p| !network.placed[*p]);
+        if let Some(i) = target {
+            while let Some(j) = (0..n).find(|j| {
+                *j != i && !set.is_empty() && set.iter().all(|p| network.parities[*p][*j])
+            }) {
+                network.cnot(j, i);
+                set.retain(|p| !network.placed[*p]);
+            }
+        }
+        if set.is_empty() || qubits.is_empty() {
+            continue;
+        }
+        let j = *qubits
+            .iter()
+            .max_by_key(|j| {
+                let ones = set.iter().filter(|p| network.parities[**p][**j]).count();
+                (ones.max(set.len() - ones), n - **j)
+            })
+            .unwrap();
+        let rest: Vec<usize> = qubits.iter().filter(|q| **q != j).copied().collect();
+        let (ones, zeros): (Vec<usize>, Vec<usize>) =
+            set.into_iter().partition(|p| network.parities[*p][j]);
+        stack.push((ones, rest.clone(), Some(target.unwrap_or(j))));
+        stack.push((zeros, rest, target));
+    }
+}
+
+/// Computes all the parities of the network using Gray-synth
+pub(crate) fn gray_synth(network: &mut ParityNetwork) {
+    loop {
+        let remaining = network.remaining();
+        if remaining.is_empty() {
+            return;
+        }
+        gray_synth_pass(network);
+        // CNOT gates applied in some branch can break the structure of the pending groups: the
+        // parities left behind are handled by another pass, or placed directly if no progress
+        // is made
+        if network.remaining().len() == remaining.len() {
+            network.place_directly(remaining[0]);
+        }
+    }
+}
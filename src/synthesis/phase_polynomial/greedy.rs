@@ -0,0 +1,89 @@
+//! Greedy heuristics building the parity network one CNOT gate (or one layer of CNOT gates) at a
+//! time.
+//!
+//! A gate `CNOT(c, t)` is scored by the number of parities it places on wire `t`, and by the
+//! decrease of the total weight of the remaining parities (expressed in the basis of the current
+//! wires). When no gate makes progress, the remaining parity of smallest weight is directly
+//! computed on one of its qubits.
+use super::common::ParityNetwork;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+/// Scores of all the CNOT gates as `(placed, gain, control, target)`, best first. Ties are
+/// broken randomly when `shuffle` is set.
+fn scored_gates(network: &ParityNetwork, shuffle: bool) -> Vec<(usize, isize, usize, usize)> {
+    let remaining = network.remaining();
+    let mut gates = Vec::new();
+    for control in 0..network.n {
+        for target in 0..network.n {
+            if control == target {
+                continue;
+            }
+            let (mut placed, mut gain) = (0, 0);
+            for p in remaining.iter() {
+                let parity = &network.parities[*p];
+                if parity[target] {
+                    if parity[control] {
+                        gain += 1;
+                        if parity.iter().filter(|b| **b).count() == 2 {
+                            placed += 1;
+                        }
+                    } else {
+                        gain -= 1;
+                    }
+                }
+            }
+            gates.push((placed, gain, control, target));
+        }
+    }
+    if shuffle {
+        gates.shuffle(&mut thread_rng());
+    }
+    gates.sort_by_key(|(placed, gain, _, _)| std::cmp::Reverse((*placed, *gain)));
+    gates
+}
+
+/// Computes the remaining parity of smallest weight
+fn place_lightest(network: &mut ParityNetwork) {
+    let lightest = network
+        .remaining()
+        .into_iter()
+        .min_by_key(|p| network.parities[*p].iter().filter(|b| **b).count())
+        .unwrap();
+    network.place_directly(lightest);
+}
+
+/// Computes all the parities of the network, one CNOT gate at a time
+pub(crate) fn greedy_count(network: &mut ParityNetwork, shuffle: bool) {
+    while !network.remaining().is_empty() {
+        match scored_gates(network, shuffle).first() {
+            Some((placed, gain, control, target)) if *placed > 0 || *gain > 0 => {
+                network.cnot(*control, *target)
+            }
+            _ => place_lightest(network),
+        }
+    }
+}
+
+/// Computes all the parities of the network, one layer of disjoint CNOT gates at a time
+pub(crate) fn layered_greedy(network: &mut ParityNetwork, shuffle: bool) {
+    while !network.remaining().is_empty() {
+        let gates: Vec<_> = scored_gates(network, shuffle)
+            .into_iter()
+            .filter(|(placed, gain, _, _)| *placed > 0 || *gain > 0)
+            .collect();
+        if gates.is_empty() {
+            place_lightest(network);
+            continue;
+        }
+        // Disjoint gates act on distinct coordinates: their scores add up
+        let mut busy = vec![false; network.n];
+        for (_, _, control, target) in gates {
+            if !busy[control] && !busy[target] {
+                busy[control] = true;
+                busy[target] = true;
+                network.cnot(control, target);
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! This module contains the synthesis algorithms for phase polynomials, i.e. products of
+//! commuting Z-rotations such as ZZ-only QAOA layers or Ising Hamiltonian evolutions.
+//!
+//! The output is a parity network made of CNOT gates, together with the positions of the RZ
+//! gates. The network is built using [Gray-synth](gray_synth) and [greedy heuristics](greedy)
+//! targeting either CNOT count or CNOT depth.
+//!
+//! ```
+//! use rustiq_core::structures::{Metric, Parameter};
+//! use rustiq_core::synthesis::phase_polynomial::phase_polynomial_synthesis;
+//! let rotations = vec![
+//!     ("ZZI".to_string(), Parameter::Abstract("a".to_string())),
+//!     ("IZZ".to_string(), Parameter::Abstract("b".to_string())),
+//!     ("ZIZ".to_string(), Parameter::Concrete(0.5)),
+//! ];
+//! let (circuit, rz_gates) = phase_polynomial_synthesis(&rotations, &Metric::COUNT, 1).unwrap();
+//! // Each RZ gate is given as (position in the circuit, qubit, angle)
+//! assert_eq!(rz_gates.len(), 3);
+//! ```
+mod common;
+pub mod gray_synth;
+pub mod greedy;
+pub mod synthesis;
+pub use synthesis::{phase_polynomial_synthesis, RzGates};
@@ -0,0 +1,187 @@
+use super::common::ParityNetwork;
+use super::gray_synth::gray_synth;
+use super::greedy::{greedy_count, layered_greedy};
+use crate::structures::{CliffordCircuit, Metric, Parameter};
+
+/// RZ gates to be inserted in a parity network, as `(position, qubit, angle)` triplets
+pub type RzGates = Vec<(usize, usize, Parameter)>;
+
+/// The number of qubits, the distinct parities and their angles
+type Parities = (usize, Vec<Vec<bool>>, Vec<Parameter>);
+
+/// Converts Z-only rotations into parities, merging rotations with identical axes and dropping
+/// global phases
+fn collect_parities(rotations: &[(String, Parameter)]) -> Result<Parities, String> {
+    let n = rotations.first().map_or(0, |(axis, _)| axis.len());
+    let mut parities: Vec<Vec<bool>> = Vec::new();
+    let mut angles: Vec<Parameter> = Vec::new();
+    for (axis, angle) in rotations.iter() {
+        if axis.len() != n {
+            return Err(format!(
+                "Rotation axis `{}` should act on {} qubits",
+                axis, n
+            ));
+        }
+        let parity = axis
+            .chars()
+            .map(|c| match c {
+                'Z' => Ok(true),
+                'I' => Ok(false),
+                _ => Err(format!("Rotation axis `{}` is not a Z-string", axis)),
+            })
+            .collect::<Result<Vec<bool>, String>>()?;
+        if parity.iter().all(|b| !*b) {
+            continue;
+        }
+        match parities.iter().position(|p| *p == parity) {
+            Some(index) => angles[index] += angle.clone(),
+            None => {
+                parities.push(parity);
+                angles.push(angle.clone());
+            }
+        }
+    }
+    let (parities, angles) = parities
+        .into_iter()
+        .zip(angles)
+        .filter(|(_, angle)| !angle.is_zero_mod_two_pi())
+        .unzip();
+    Ok((n, parities, angles))
+}
+
+/// Synthesizes a CNOT+RZ circuit implementing a product of Z-rotations (i.e. a phase polynomial).
+///
+/// Each rotation is given by a Z-string (e.g. `"ZIZ"`) and an angle. Since all the rotations
+/// commute, their order is irrelevant. The output is a CNOT circuit implementing the identity,
+/// together with a list of `(position, qubit, angle)`: an RZ gate of the given angle should be
+/// applied on `qubit` right after the first `position` gates of the circuit.
+///
+/// The parity network is built with Gray-synth and with greedy heuristics, run `niter` times with
+/// random tie-breaking. The final linear map is restored using
+/// [linear_synthesis](crate::synthesis::linear::linear_synthesis), and the best circuit according
+/// to `metric` is returned.
+pub fn phase_polynomial_synthesis(
+    rotations: &[(String, Parameter)],
+    metric: &Metric,
+    niter: usize,
+) -> Result<(CliffordCircuit, RzGates), String> {
+    let (n, parities, angles) = collect_parities(rotations)?;
+    let network = ParityNetwork::new(n, parities, angles);
+    let greedy = match metric {
+        Metric::COUNT => greedy_count,
+        Metric::DEPTH => layered_greedy,
+    };
+    let mut candidates = vec![network.clone()];
+    gray_synth(&mut candidates[0]);
+    for iteration in 0..niter.max(1) {
+        let mut candidate = network.clone();
+        greedy(&mut candidate, iteration > 0);
+        candidates.push(candidate);
+    }
+    let best = candidates
+        .into_iter()
+        .map(|candidate| candidate.finish(metric, niter))
+        .min_by_key(|(circuit, _)| (metric.on_circuit(circuit), circuit.gates.len()))
+        .unwrap();
+    Ok(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routines::f2_linalg::rowop;
+    use crate::structures::CliffordGate;
+    use crate::synthesis::linear::common::identity;
+    use rand::Rng;
+
+    fn random_rotations(n: usize, m: usize) -> Vec<(String, Parameter)> {
+        let mut rng = rand::thread_rng();
+        (0..m)
+            .map(|k| {
+                let axis: String = (0..n)
+                    .map(|_| if rng.gen_bool(0.5) { 'Z' } else { 'I' })
+                    .collect();
+                (axis, Parameter::Abstract(format!("t{}", k)))
+            })
+            .collect()
+    }
+
+    /// Checks that each rotation is applied on a wire carrying its parity, and that the circuit
+    /// implements the identity
+    fn check_synthesis(
+        rotations: &[(String, Parameter)],
+        circuit: &CliffordCircuit,
+        placements: &RzGates,
+    ) {
+        let (n, parities, angles) = collect_parities(rotations).unwrap();
+        let mut wires = identity(n);
+        let mut seen = vec![false; parities.len()];
+        let mut check_position = |position: usize, wires: &Vec<Vec<bool>>| {
+            for (_, qbit, angle) in placements.iter().filter(|(p, _, _)| *p == position) {
+                let index = parities
+                    .iter()
+                    .position(|parity| *parity == wires[*qbit])
+                    .expect("Rotation applied on a wrong parity");
+                assert_eq!(angles[index], *angle);
+                assert!(!seen[index]);
+                seen[index] = true;
+            }
+        };
+        check_position(0, &wires);
+        for (index, gate) in circuit.gates.iter().enumerate() {
+            match gate {
+                CliffordGate::CNOT(i, j) => rowop(&mut wires, *i, *j),
+                _ => panic!("Unexpected gate {:?}", gate),
+            }
+            check_position(index + 1, &wires);
+        }
+        assert!(seen.iter().all(|b| *b));
+        assert_eq!(wires, identity(n));
+    }
+
+    #[test]
+    fn test_phase_polynomial() {
+        for metric in [Metric::COUNT, Metric::DEPTH] {
+            for m in [1, 5, 20, 60] {
+                for _ in 0..5 {
+                    let rotations = random_rotations(6, m);
+                    let (circuit, placements) =
+                        phase_polynomial_synthesis(&rotations, &metric, 1).unwrap();
+                    check_synthesis(&rotations, &circuit, &placements);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ising_chain() {
+        let rotations: Vec<(String, Parameter)> = ["ZZII", "IZZI", "IIZZ", "ZIII"]
+            .iter()
+            .map(|axis| (axis.to_string(), Parameter::Concrete(0.3)))
+            .collect();
+        let (circuit, placements) =
+            phase_polynomial_synthesis(&rotations, &Metric::COUNT, 1).unwrap();
+        check_synthesis(&rotations, &circuit, &placements);
+        assert!(circuit.cnot_count() <= 6);
+    }
+
+    #[test]
+    fn test_merge_and_errors() {
+        let rotations = vec![
+            ("ZZ".to_string(), Parameter::Concrete(0.5)),
+            ("ZZ".to_string(), Parameter::Concrete(-0.5)),
+            ("II".to_string(), Parameter::Concrete(1.0)),
+        ];
+        let (circuit, placements) =
+            phase_polynomial_synthesis(&rotations, &Metric::COUNT, 1).unwrap();
+        assert!(circuit.gates.is_empty());
+        assert!(placements.is_empty());
+        let rotations = vec![("XZ".to_string(), Parameter::Concrete(0.5))];
+        assert!(phase_polynomial_synthesis(&rotations, &Metric::COUNT, 1).is_err());
+        let rotations = vec![
+            ("ZZ".to_string(), Parameter::Concrete(0.5)),
+            ("Z".to_string(), Parameter::Concrete(0.5)),
+        ];
+        assert!(phase_polynomial_synthesis(&rotations, &Metric::DEPTH, 1).is_err());
+    }
+}
@@ -0,0 +1,574 @@
+//! Equivalence checks between Clifford circuits, tableaux and isometries.
+//!
+//! Operators are compared through the images of their generators: `X_i` and `Z_i` denote the
+//! images of X and Z on (logical) qubit `i` and, for isometries, `S_i` denotes the `i`-th
+//! stabilizer.
+//!
+//! ```
+//! use rustiq_core::structures::{CliffordCircuit, CliffordGate};
+//! use rustiq_core::verification::{check_circuits, Equivalence};
+//! let mut c1 = CliffordCircuit::new(2);
+//! c1.gates.push(CliffordGate::H(0));
+//! let mut c2 = c1.clone();
+//! // Z = S S on qubit 0 only flips the sign of the image of Z_0 = X
+//! c2.gates.push(CliffordGate::S(0));
+//! c2.gates.push(CliffordGate::S(0));
+//! let mismatch = check_circuits(&c1, &c2, Equivalence::EXACT).unwrap_err();
+//! assert_eq!(mismatch.to_string(), "Z_0 is mapped to -XI instead of +XI");
+//! assert!(check_circuits(&c1, &c2, Equivalence::UP_TO_PAULI).is_ok());
+//! ```
+use crate::structures::{CliffordCircuit, IsometryTableau, PauliLike, PauliSet, Tableau};
+use std::fmt;
+
+/// The notion of equality used when comparing two Clifford operators. Relaxations can be
+/// combined, e.g. `Equivalence::UP_TO_PAULI.and(Equivalence::UP_TO_STABILIZERS)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Equivalence {
+    /// The operators are equal up to a Pauli operator applied at the end, i.e. the signs of the
+    /// images are ignored
+    pub up_to_pauli: bool,
+    /// The operators are equal up to a permutation of their output qubits. The permutation is
+    /// recovered by matching the columns of the two tableaux: combined with `up_to_stabilizers`,
+    /// it is only found if the generators of the two isometries coincide up to signs.
+    pub up_to_permutation: bool,
+    /// The isometries are equal as isometries: they have the same stabilizer group and their
+    /// logical operators coincide up to multiplication by a stabilizer. This has no effect on
+    /// tableaux.
+    pub up_to_stabilizers: bool,
+}
+
+impl Equivalence {
+    /// The images of all generators are equal, signs included
+    pub const EXACT: Self = Self {
+        up_to_pauli: false,
+        up_to_permutation: false,
+        up_to_stabilizers: false,
+    };
+    pub const UP_TO_PAULI: Self = Self {
+        up_to_pauli: true,
+        ..Self::EXACT
+    };
+    pub const UP_TO_PERMUTATION: Self = Self {
+        up_to_permutation: true,
+        ..Self::EXACT
+    };
+    pub const UP_TO_STABILIZERS: Self = Self {
+        up_to_stabilizers: true,
+        ..Self::EXACT
+    };
+
+    /// Combines the relaxations of two equivalences
+    pub fn and(self, other: Self) -> Self {
+        Self {
+            up_to_pauli: self.up_to_pauli || other.up_to_pauli,
+            up_to_permutation: self.up_to_permutation || other.up_to_permutation,
+            up_to_stabilizers: self.up_to_stabilizers || other.up_to_stabilizers,
+        }
+    }
+}
+
+/// Describes why two Clifford operators differ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The operators have different shapes, given as (number of logical qubits, number of
+    /// stabilizers)
+    Dimensions {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    /// The first generator whose images differ, images being given as (phase, string) pairs
+    Operator {
+        name: String,
+        expected: (bool, String),
+        found: (bool, String),
+    },
+    /// No permutation of the output qubits matches the two operators: the column of output
+    /// `qubit` of the expected operator has no counterpart
+    NoPermutation { qubit: usize },
+}
+
+fn signed(operator: &(bool, String)) -> String {
+    format!("{}{}", if operator.0 { '-' } else { '+' }, operator.1)
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Dimensions { expected, found } => write!(
+                f,
+                "Expected an operator with {} qubits and {} stabilizers, found {} qubits and {} stabilizers",
+                expected.0, expected.1, found.0, found.1
+            ),
+            Self::Operator {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} is mapped to {} instead of {}",
+                name,
+                signed(found),
+                signed(expected)
+            ),
+            Self::NoPermutation { qubit } => write!(
+                f,
+                "No output qubit matches output qubit {} up to a permutation",
+                qubit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+/// A list of named generator images
+type Images = Vec<(String, (bool, String))>;
+
+fn logical_images(logicals: &PauliSet, n: usize) -> Images {
+    (0..2 * n)
+        .map(|i| {
+            let name = if i < n {
+                format!("X_{}", i)
+            } else {
+                format!("Z_{}", i - n)
+            };
+            (name, logicals.get(i))
+        })
+        .collect()
+}
+
+fn stabilizer_images(stabilizers: &PauliSet) -> Images {
+    (0..stabilizers.len())
+        .map(|i| (format!("S_{}", i), stabilizers.get(i)))
+        .collect()
+}
+
+fn mismatch(name: &str, expected: &(bool, String), found: &(bool, String)) -> Mismatch {
+    Mismatch::Operator {
+        name: name.to_owned(),
+        expected: expected.clone(),
+        found: found.clone(),
+    }
+}
+
+/// Returns the first generator whose images differ
+fn first_difference(
+    expected: &Images,
+    found: &Images,
+    ignore_phases: bool,
+) -> Result<(), Mismatch> {
+    for ((name, a), (_, b)) in expected.iter().zip(found.iter()) {
+        if a.1 != b.1 || (!ignore_phases && a.0 != b.0) {
+            return Err(mismatch(name, a, b));
+        }
+    }
+    Ok(())
+}
+
+/// Relabels the output qubits of `images`: qubit `permutation[q]` becomes qubit `q`
+fn permute(images: &Images, permutation: &[usize]) -> Images {
+    images
+        .iter()
+        .map(|(name, (phase, string))| {
+            let permuted = permutation
+                .iter()
+                .map(|q| string.as_bytes()[*q] as char)
+                .collect();
+            (name.clone(), (*phase, permuted))
+        })
+        .collect()
+}
+
+/// Finds a permutation `p` of the output qubits such that qubit `q` of `expected` corresponds to
+/// qubit `p[q]` of `found`, by matching the columns of the two tableaux. Returns the first qubit
+/// of `expected` without a matching column if there is none.
+fn find_permutation(expected: &Images, found: &Images, nqbits: usize) -> Result<Vec<usize>, usize> {
+    let column = |images: &Images, q: usize| -> Vec<u8> {
+        images
+            .iter()
+            .map(|(_, (_, string))| string.as_bytes()[q])
+            .collect()
+    };
+    let found_columns: Vec<Vec<u8>> = (0..nqbits).map(|q| column(found, q)).collect();
+    let mut used = vec![false; nqbits];
+    let mut permutation = Vec::with_capacity(nqbits);
+    for q in 0..nqbits {
+        let target = column(expected, q);
+        let p = (0..nqbits)
+            .find(|p| !used[*p] && found_columns[*p] == target)
+            .ok_or(q)?;
+        used[p] = true;
+        permutation.push(p);
+    }
+    Ok(permutation)
+}
+
+/// Multiplies two Pauli operators given as (phase, string) pairs. Returns None if the product is
+/// not Hermitian, i.e. if the operators anticommute.
+pub(crate) fn product(a: &(bool, String), b: &(bool, String)) -> Option<(bool, String)> {
+    let mut i_power = 2 * (a.0 as usize + b.0 as usize);
+    let string =
+        a.1.chars()
+            .zip(b.1.chars())
+            .map(|(x, y)| match (x, y) {
+                ('I', _) => y,
+                (_, 'I') => x,
+                _ if x == y => 'I',
+                ('X', 'Y') | ('Y', 'Z') | ('Z', 'X') => {
+                    i_power += 1;
+                    ['X', 'Y', 'Z']
+                        .into_iter()
+                        .find(|c| *c != x && *c != y)
+                        .unwrap()
+                }
+                _ => {
+                    i_power += 3;
+                    ['X', 'Y', 'Z']
+                        .into_iter()
+                        .find(|c| *c != x && *c != y)
+                        .unwrap()
+                }
+            })
+            .collect();
+    match i_power % 4 {
+        0 => Some((false, string)),
+        2 => Some((true, string)),
+        _ => None,
+    }
+}
+
+fn to_bits(string: &str) -> Vec<bool> {
+    let x = string.chars().map(|c| c == 'X' || c == 'Y');
+    let z = string.chars().map(|c| c == 'Z' || c == 'Y');
+    x.chain(z).collect()
+}
+
+/// Checks whether `operator` belongs to the group generated by the pairwise commuting
+/// `generators`, signs included unless `ignore_phase` is set
+fn in_group(generators: &[(bool, String)], operator: &(bool, String), ignore_phase: bool) -> bool {
+    // Gaussian elimination keeping track of the generators involved in each row
+    let mut rows: Vec<(Vec<bool>, Vec<bool>)> = generators
+        .iter()
+        .enumerate()
+        .map(|(i, (_, string))| {
+            let mut combination = vec![false; generators.len()];
+            combination[i] = true;
+            (to_bits(string), combination)
+        })
+        .collect();
+    let mut target = (to_bits(&operator.1), vec![false; generators.len()]);
+    let mut rank = 0;
+    for col in 0..target.0.len() {
+        let Some(pivot) = (rank..rows.len()).find(|r| rows[*r].0[col]) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        for r in 0..rows.len() {
+            if r != rank && rows[r].0[col] {
+                let pivot_row = rows[rank].clone();
+                for (a, b) in rows[r].0.iter_mut().zip(pivot_row.0.iter()) {
+                    *a ^= b;
+                }
+                for (a, b) in rows[r].1.iter_mut().zip(pivot_row.1.iter()) {
+                    *a ^= b;
+                }
+            }
+        }
+        if target.0[col] {
+            for (a, b) in target.0.iter_mut().zip(rows[rank].0.iter()) {
+                *a ^= b;
+            }
+            for (a, b) in target.1.iter_mut().zip(rows[rank].1.iter()) {
+                *a ^= b;
+            }
+        }
+        rank += 1;
+    }
+    if target.0.iter().any(|b| *b) {
+        return false;
+    }
+    if ignore_phase {
+        return true;
+    }
+    let identity = (false, "I".repeat(operator.1.len()));
+    let element = generators
+        .iter()
+        .zip(target.1.iter())
+        .filter(|(_, used)| **used)
+        .try_fold(identity, |acc, (generator, _)| product(&acc, generator));
+    element.as_ref() == Some(operator)
+}
+
+fn check_shapes(expected: (usize, usize), found: (usize, usize)) -> Result<(), Mismatch> {
+    if expected != found {
+        return Err(Mismatch::Dimensions { expected, found });
+    }
+    Ok(())
+}
+
+/// Applies the output permutation relaxation, returning the images of `found` to compare with
+/// those of `expected`
+fn align(
+    expected: &Images,
+    found: &Images,
+    nqbits: usize,
+    equivalence: Equivalence,
+) -> Result<Images, Mismatch> {
+    if !equivalence.up_to_permutation {
+        return Ok(found.clone());
+    }
+    match find_permutation(expected, found, nqbits) {
+        Ok(permutation) => Ok(permute(found, &permutation)),
+        Err(qubit) => {
+            // Signs and Pauli letter counts are invariant under permutation: a generator on which
+            // they differ is mismatched whatever the permutation
+            let invariants = |(phase, string): &(bool, String)| {
+                let count = |c: char| string.chars().filter(|x| *x == c).count();
+                (
+                    *phase && !equivalence.up_to_pauli,
+                    count('X'),
+                    count('Y'),
+                    count('Z'),
+                )
+            };
+            for ((name, a), (_, b)) in expected.iter().zip(found.iter()) {
+                if invariants(a) != invariants(b) {
+                    return Err(mismatch(name, a, b));
+                }
+            }
+            Err(Mismatch::NoPermutation { qubit })
+        }
+    }
+}
+
+/// Checks that two tableaux are equal
+pub fn compare_tableaux(
+    expected: &Tableau,
+    found: &Tableau,
+    equivalence: Equivalence,
+) -> Result<(), Mismatch> {
+    let n = expected.logicals.n;
+    check_shapes((n, 0), (found.logicals.n, 0))?;
+    let expected = logical_images(&expected.logicals, n);
+    let found = align(
+        &expected,
+        &logical_images(&found.logicals, n),
+        n,
+        equivalence,
+    )?;
+    first_difference(&expected, &found, equivalence.up_to_pauli)
+}
+
+/// Checks that two isometries are equal
+pub fn compare_isometries(
+    expected: &IsometryTableau,
+    found: &IsometryTableau,
+    equivalence: Equivalence,
+) -> Result<(), Mismatch> {
+    let (n, k) = (expected.n, expected.k);
+    check_shapes((n, k), (found.n, found.k))?;
+    let mut expected_images = logical_images(&expected.logicals, n);
+    expected_images.extend(stabilizer_images(&expected.stabilizers));
+    let mut found_images = logical_images(&found.logicals, n);
+    found_images.extend(stabilizer_images(&found.stabilizers));
+    let found_images = align(&expected_images, &found_images, n + k, equivalence)?;
+    let ignore_phases = equivalence.up_to_pauli;
+    if !equivalence.up_to_stabilizers {
+        return first_difference(&expected_images, &found_images, ignore_phases);
+    }
+    let (expected_logicals, expected_stabilizers) = expected_images.split_at(2 * n);
+    let (found_logicals, found_stabilizers) = found_images.split_at(2 * n);
+    let expected_group: Vec<(bool, String)> = expected_stabilizers
+        .iter()
+        .map(|(_, s)| s.clone())
+        .collect();
+    let found_group: Vec<(bool, String)> =
+        found_stabilizers.iter().map(|(_, s)| s.clone()).collect();
+    for ((name, a), (_, b)) in expected_stabilizers.iter().zip(found_stabilizers.iter()) {
+        if !in_group(&expected_group, b, ignore_phases) || !in_group(&found_group, a, ignore_phases)
+        {
+            return Err(mismatch(name, a, b));
+        }
+    }
+    for ((name, a), (_, b)) in expected_logicals.iter().zip(found_logicals.iter()) {
+        let equal = match product(a, b) {
+            Some(difference) => in_group(&expected_group, &difference, ignore_phases),
+            None => false,
+        };
+        if !equal {
+            return Err(mismatch(name, a, b));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that two Clifford circuits implement the same operator. The circuits are compared over
+/// the largest of their qubit counts.
+pub fn check_circuits(
+    expected: &CliffordCircuit,
+    found: &CliffordCircuit,
+    equivalence: Equivalence,
+) -> Result<(), Mismatch> {
+    let nqbits = expected.nqbits.max(found.nqbits);
+    let mut expected_tableau = Tableau::new(nqbits);
+    expected_tableau.conjugate_with_circuit(expected);
+    let mut found_tableau = Tableau::new(nqbits);
+    found_tableau.conjugate_with_circuit(found);
+    compare_tableaux(&expected_tableau, &found_tableau, equivalence)
+}
+
+/// Checks that a Clifford circuit implements a tableau
+pub fn check_circuit_tableau(
+    circuit: &CliffordCircuit,
+    expected: &Tableau,
+    equivalence: Equivalence,
+) -> Result<(), Mismatch> {
+    let mut found = Tableau::new(expected.logicals.n);
+    found.conjugate_with_circuit(circuit);
+    compare_tableaux(expected, &found, equivalence)
+}
+
+/// Checks that a Clifford circuit implements an isometry. The circuit is applied to the `n`
+/// logical qubits followed by `k` qubits in the |0> state.
+pub fn check_circuit_isometry(
+    circuit: &CliffordCircuit,
+    expected: &IsometryTableau,
+    equivalence: Equivalence,
+) -> Result<(), Mismatch> {
+    let mut found = IsometryTableau::new(expected.n, expected.k);
+    found.conjugate_with_circuit(circuit);
+    compare_isometries(expected, &found, equivalence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::{CliffordGate, Metric};
+    use crate::synthesis::clifford::isometry::isometry_synthesis;
+
+    #[test]
+    fn test_pauli_corrections() {
+        let circuit = CliffordCircuit::random(3, 30);
+        let mut corrected = circuit.clone();
+        corrected.gates.push(CliffordGate::SqrtX(1));
+        corrected.gates.push(CliffordGate::SqrtX(1));
+        assert!(check_circuits(&circuit, &circuit, Equivalence::EXACT).is_ok());
+        assert!(check_circuits(&circuit, &corrected, Equivalence::UP_TO_PAULI).is_ok());
+        match check_circuits(&circuit, &corrected, Equivalence::EXACT) {
+            Err(Mismatch::Operator {
+                expected, found, ..
+            }) => {
+                assert_eq!(expected.1, found.1);
+                assert_ne!(expected.0, found.0);
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+        let mut different = circuit.clone();
+        different.gates.push(CliffordGate::S(2));
+        assert!(check_circuits(&circuit, &different, Equivalence::UP_TO_PAULI).is_err());
+    }
+
+    #[test]
+    fn test_permutations() {
+        let circuit = CliffordCircuit::random(4, 40);
+        let mut permuted = circuit.clone();
+        for (a, b) in [(0, 2), (2, 0), (0, 2), (1, 3), (3, 1), (1, 3)] {
+            permuted.gates.push(CliffordGate::CNOT(a, b));
+        }
+        assert!(check_circuits(&circuit, &permuted, Equivalence::EXACT).is_err());
+        assert!(check_circuits(&circuit, &permuted, Equivalence::UP_TO_PERMUTATION).is_ok());
+        let tableau = Tableau::from_circuit(&circuit);
+        assert!(check_circuit_tableau(&permuted, &tableau, Equivalence::UP_TO_PERMUTATION).is_ok());
+        permuted.gates.push(CliffordGate::H(0));
+        assert!(check_circuits(&circuit, &permuted, Equivalence::UP_TO_PERMUTATION).is_err());
+    }
+
+    #[test]
+    fn test_permutation_diagnostics() {
+        let operators = |strings: [&str; 4]| -> Tableau {
+            Tableau::from_operators(&strings.iter().map(|s| (false, s.to_string())).collect())
+        };
+        let expected = operators(["XI", "IX", "ZI", "IZ"]);
+        let found = operators(["XX", "IX", "ZI", "ZZ"]);
+        let mismatch = compare_tableaux(&expected, &found, Equivalence::UP_TO_PERMUTATION);
+        assert_eq!(
+            mismatch.unwrap_err().to_string(),
+            "X_0 is mapped to +XX instead of +XI"
+        );
+        // Invalid tableau whose images have the right Pauli letters but mismatching columns
+        let found = operators(["IX", "XI", "ZI", "IZ"]);
+        let mismatch = compare_tableaux(&expected, &found, Equivalence::UP_TO_PERMUTATION);
+        assert_eq!(mismatch, Err(Mismatch::NoPermutation { qubit: 0 }));
+    }
+
+    #[test]
+    fn test_isometries() {
+        for _ in 0..10 {
+            let (n, k) = (4, 3);
+            let isometry = IsometryTableau::random(n, k);
+            let circuit = isometry_synthesis(&isometry, &Metric::COUNT, 1);
+            // The synthesis only fixes the phases of unitary operators
+            let relaxed = Equivalence::UP_TO_STABILIZERS.and(Equivalence::UP_TO_PAULI);
+            assert!(check_circuit_isometry(&circuit, &isometry, relaxed).is_ok());
+
+            // Multiplying a logical operator by a stabilizer yields the same isometry
+            let mut other = isometry.clone();
+            let logical = other.logicals.get(n);
+            let stabilizer = other.stabilizers.get(0);
+            let (phase, string) = product(&logical, &stabilizer).unwrap();
+            let mut logicals = PauliSet::new(n + k);
+            for i in 0..2 * n {
+                let (p, s) = if i == n {
+                    (phase, string.clone())
+                } else {
+                    other.logicals.get(i)
+                };
+                logicals.insert(&s, p);
+            }
+            other.logicals = logicals;
+            match compare_isometries(&isometry, &other, Equivalence::EXACT) {
+                Err(Mismatch::Operator { name, .. }) => assert_eq!(name, "Z_0"),
+                other => panic!("Unexpected result {:?}", other),
+            }
+            assert!(compare_isometries(&isometry, &other, Equivalence::UP_TO_STABILIZERS).is_ok());
+
+            // Flipping the sign of a stabilizer changes the isometry
+            let mut flipped = isometry.clone();
+            flipped
+                .stabilizers
+                .set_phase(1, !flipped.stabilizers.get_phase(1));
+            match compare_isometries(&isometry, &flipped, Equivalence::UP_TO_STABILIZERS) {
+                Err(Mismatch::Operator { name, .. }) => assert_eq!(name, "S_1"),
+                other => panic!("Unexpected result {:?}", other),
+            }
+        }
+        assert_eq!(
+            compare_isometries(
+                &IsometryTableau::new(2, 1),
+                &IsometryTableau::new(3, 0),
+                Equivalence::EXACT
+            ),
+            Err(Mismatch::Dimensions {
+                expected: (2, 1),
+                found: (3, 0)
+            })
+        );
+    }
+
+    #[test]
+    fn test_group_membership() {
+        let generators = vec![(false, "ZZI".to_owned()), (true, "IZZ".to_owned())];
+        assert!(in_group(&generators, &(true, "ZIZ".to_owned()), false));
+        assert!(!in_group(&generators, &(false, "ZIZ".to_owned()), false));
+        assert!(!in_group(&generators, &(false, "ZII".to_owned()), true));
+        assert!(in_group(&generators, &(false, "III".to_owned()), false));
+        assert_eq!(
+            product(&(false, "XY".to_owned()), &(false, "YX".to_owned())),
+            Some((false, "ZZ".to_owned()))
+        );
+        assert_eq!(
+            product(&(false, "X".to_owned()), &(false, "Z".to_owned())),
+            None
+        );
+    }
+}
@@ -0,0 +1,12 @@
+//! This module contains helpers checking that synthesized circuits implement their target
+//!
+//! * [clifford] - phase-aware equivalence checks of Clifford circuits, tableaux and isometries
+//!
+//! Circuits containing rotations can be checked by simulation using
+//! [circuits_equivalent](crate::simulation::circuits_equivalent).
+pub mod clifford;
+
+pub use clifford::{
+    check_circuit_isometry, check_circuit_tableau, check_circuits, compare_isometries,
+    compare_tableaux, Equivalence, Mismatch,
+};